@@ -2,20 +2,36 @@ use anyhow::*;
 use fs_extra::copy_items;
 use fs_extra::dir::CopyOptions;
 use std::env;
+use std::fs;
+use std::path::Path;
 
 fn main() -> Result<()> {
     println!("cargo:rerun-if-changed=resources/");
 
-    // desktop
     let out_dir = env::var("OUT_DIR")?;
     let mut copy_options = CopyOptions::new();
     copy_options.overwrite = true;
     let paths_to_copy = vec!["resources/"];
-    copy_items(&paths_to_copy, out_dir, &copy_options)?;
-
+    // desktop
+    copy_items(&paths_to_copy, &out_dir, &copy_options)?;
     // web
-    let out_dir = "./web";
-    copy_items(&paths_to_copy, out_dir, &copy_options)?;
+    let web_dir = "./web";
+    copy_items(&paths_to_copy, web_dir, &copy_options)?;
+
+    // tutorial shaders as `shaders/<chapter>/<file>`, so they can be loaded at runtime too
+    for shaders_dir in glob::glob("src/*/shaders")? {
+        let shaders_dir = shaders_dir?;
+        println!("cargo:rerun-if-changed={}", shaders_dir.display());
+        let chapter = shaders_dir.parent().unwrap().file_name().unwrap();
+        let shaders = fs::read_dir(&shaders_dir)?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        for dir in [out_dir.as_str(), web_dir] {
+            let dest = Path::new(dir).join("resources/shaders").join(chapter);
+            fs::create_dir_all(&dest)?;
+            copy_items(&shaders, &dest, &copy_options)?;
+        }
+    }
 
     Ok(())
 }
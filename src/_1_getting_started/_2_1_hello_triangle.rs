@@ -1,3 +1,4 @@
+use crate::gl_object::{GlBuffer, GlProgram, GlVertexArray};
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use glow::*;
 use std::mem::size_of;
@@ -18,22 +19,20 @@ const VERTICES: [f32; 9] = [
 ];
 
 struct App {
-    vao: VertexArray,
-    vbo: Buffer,
-    program: Program,
+    vao: GlVertexArray,
+    _vbo: GlBuffer,
+    program: GlProgram,
 }
 
 impl Application for App {
     async unsafe fn new(ctx: &AppContext) -> Self {
         let gl = ctx.gl();
         let shader_version = ctx.suggested_shader_version();
-        let vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        let vbo = gl.create_buffer().expect("Cannot create buffer");
-        gl.bind_vertex_array(Some(vao));
+        let vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        let vbo = GlBuffer::new(gl).expect("Cannot create buffer");
+        gl.bind_vertex_array(Some(vao.raw()));
 
-        gl.bind_buffer(ARRAY_BUFFER, Some(vbo));
+        gl.bind_buffer(ARRAY_BUFFER, Some(vbo.raw()));
         gl.buffer_data_u8_slice(ARRAY_BUFFER, bytemuck::cast_slice(&VERTICES), STATIC_DRAW);
 
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, 3 * size_of::<f32>() as i32, 0);
@@ -46,7 +45,7 @@ impl Application for App {
         // VAOs requires a call to glBindVertexArray anyway, so we generally don't unbind VAOs (nor VBOs) when it's not directly necessary.
         gl.bind_vertex_array(None);
 
-        let program = gl.create_program().expect("Cannot create program");
+        let program = GlProgram::new(gl).expect("Cannot create program");
 
         let (vertex_shader_source, fragment_shader_source) = (
             r#"layout (location = 0) in vec3 aPos;
@@ -80,30 +79,34 @@ impl Application for App {
             if !gl.get_shader_compile_status(shader) {
                 panic!("{}", gl.get_shader_info_log(shader));
             }
-            gl.attach_shader(program, shader);
+            gl.attach_shader(program.raw(), shader);
             shaders.push(shader);
         }
 
-        gl.link_program(program);
-        if !gl.get_program_link_status(program) {
-            panic!("{}", gl.get_program_info_log(program));
+        gl.link_program(program.raw());
+        if !gl.get_program_link_status(program.raw()) {
+            panic!("{}", gl.get_program_info_log(program.raw()));
         }
 
         for shader in shaders {
-            gl.detach_shader(program, shader);
+            gl.detach_shader(program.raw(), shader);
             gl.delete_shader(shader);
         }
-        Self { vao, vbo, program }
+        Self {
+            vao,
+            _vbo: vbo,
+            program,
+        }
     }
 
     unsafe fn render(&mut self, ctx: &AppContext) {
         let gl = ctx.gl();
         gl.clear_color(0.2, 0.3, 0.3, 1.0);
         gl.clear(COLOR_BUFFER_BIT);
-        gl.use_program(Some(self.program));
+        gl.use_program(Some(self.program.raw()));
         // seeing as we only have a single VAO there's no need to bind it every time,
         // but we'll do so to keep things a bit more organized
-        gl.bind_vertex_array(Some(self.vao));
+        gl.bind_vertex_array(Some(self.vao.raw()));
         gl.draw_arrays(TRIANGLES, 0, 3);
     }
 
@@ -111,14 +114,4 @@ impl Application for App {
         let gl = ctx.gl();
         gl.viewport(0, 0, width as i32, height as i32);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        gl.delete_program(self.program);
-
-        gl.delete_vertex_array(self.vao);
-
-        gl.delete_buffer(self.vbo);
-    }
 }
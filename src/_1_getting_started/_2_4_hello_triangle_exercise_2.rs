@@ -1,3 +1,4 @@
+use crate::gl_object::{GlBuffer, GlProgram, GlVertexArray};
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use glow::*;
 use std::mem::size_of;
@@ -23,29 +24,25 @@ const SECOND_TRIANGLE: [f32; 9] = [
 ];
 
 struct App {
-    first_vao: VertexArray,
-    first_vbo: Buffer,
-    second_vao: VertexArray,
-    second_vbo: Buffer,
-    program: Program,
+    first_vao: GlVertexArray,
+    _first_vbo: GlBuffer,
+    second_vao: GlVertexArray,
+    _second_vbo: GlBuffer,
+    program: GlProgram,
 }
 
 impl Application for App {
     async unsafe fn new(ctx: &AppContext) -> Self {
         let gl = ctx.gl();
         let shader_version = ctx.suggested_shader_version();
-        let first_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        let first_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        let second_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        let second_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-
-        gl.bind_vertex_array(Some(first_vao));
-
-        gl.bind_buffer(ARRAY_BUFFER, Some(first_vbo));
+        let first_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        let first_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        let second_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        let second_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+
+        gl.bind_vertex_array(Some(first_vao.raw()));
+
+        gl.bind_buffer(ARRAY_BUFFER, Some(first_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&FIRST_TRIANGLE),
@@ -55,9 +52,9 @@ impl Application for App {
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, 3 * size_of::<f32>() as i32, 0);
         gl.enable_vertex_attrib_array(0);
 
-        gl.bind_vertex_array(Some(second_vao));
+        gl.bind_vertex_array(Some(second_vao.raw()));
 
-        gl.bind_buffer(ARRAY_BUFFER, Some(second_vbo));
+        gl.bind_buffer(ARRAY_BUFFER, Some(second_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&SECOND_TRIANGLE),
@@ -74,7 +71,7 @@ impl Application for App {
         // VAOs requires a call to glBindVertexArray anyway, so we generally don't unbind VAOs (nor VBOs) when it's not directly necessary.
         gl.bind_vertex_array(None);
 
-        let program = gl.create_program().expect("Cannot create program");
+        let program = GlProgram::new(gl).expect("Cannot create program");
 
         let (vertex_shader_source, fragment_shader_source) = (
             r#"layout (location = 0) in vec3 aPos;
@@ -108,25 +105,25 @@ impl Application for App {
             if !gl.get_shader_compile_status(shader) {
                 panic!("{}", gl.get_shader_info_log(shader));
             }
-            gl.attach_shader(program, shader);
+            gl.attach_shader(program.raw(), shader);
             shaders.push(shader);
         }
 
-        gl.link_program(program);
-        if !gl.get_program_link_status(program) {
-            panic!("{}", gl.get_program_info_log(program));
+        gl.link_program(program.raw());
+        if !gl.get_program_link_status(program.raw()) {
+            panic!("{}", gl.get_program_info_log(program.raw()));
         }
 
         for shader in shaders {
-            gl.detach_shader(program, shader);
+            gl.detach_shader(program.raw(), shader);
             gl.delete_shader(shader);
         }
 
         Self {
             first_vao,
-            first_vbo,
+            _first_vbo: first_vbo,
             second_vao,
-            second_vbo,
+            _second_vbo: second_vbo,
             program,
         }
     }
@@ -135,27 +132,13 @@ impl Application for App {
         let gl = ctx.gl();
         gl.clear_color(0.2, 0.3, 0.3, 1.0);
         gl.clear(COLOR_BUFFER_BIT);
-        gl.use_program(Some(self.program));
+        gl.use_program(Some(self.program.raw()));
 
-        gl.bind_vertex_array(Some(self.first_vao));
+        gl.bind_vertex_array(Some(self.first_vao.raw()));
         gl.draw_arrays(TRIANGLES, 0, 3);
 
-        gl.bind_vertex_array(Some(self.second_vao));
+        gl.bind_vertex_array(Some(self.second_vao.raw()));
         gl.draw_arrays(TRIANGLES, 0, 3);
         // gl.bind_vertex_array(None); // no need to unbind it every time
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        gl.delete_program(self.program);
-
-        gl.delete_vertex_array(self.first_vao);
-
-        gl.delete_buffer(self.first_vbo);
-
-        gl.delete_vertex_array(self.second_vao);
-
-        gl.delete_buffer(self.second_vbo);
-    }
 }
@@ -1,4 +1,5 @@
-use crate::window::{run, AppContext, Application, WindowInitInfo};
+use crate::gl_object::{GlBuffer, GlProgram, GlVertexArray};
+use crate::window::{run, AppContext, Application, SharedGl, WindowInitInfo};
 use glow::*;
 use std::mem::size_of;
 
@@ -18,23 +19,21 @@ const VERTICES: [f32; 9] = [
 ];
 
 struct App {
-    vao: VertexArray,
-    vbo: Buffer,
-    program: Program,
+    vao: GlVertexArray,
+    _vbo: GlBuffer,
+    program: GlProgram,
 }
 
 impl Application for App {
     async unsafe fn new(ctx: &AppContext) -> Self {
         let gl = ctx.gl();
         let shader_version = ctx.suggested_shader_version();
-        let vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        let vbo = gl.create_buffer().expect("Cannot create vbo buffer");
+        let vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        let vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
 
-        gl.bind_vertex_array(Some(vao));
+        gl.bind_vertex_array(Some(vao.raw()));
 
-        gl.bind_buffer(ARRAY_BUFFER, Some(vbo));
+        gl.bind_buffer(ARRAY_BUFFER, Some(vbo.raw()));
         gl.buffer_data_u8_slice(ARRAY_BUFFER, bytemuck::cast_slice(&VERTICES), STATIC_DRAW);
 
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, 3 * size_of::<f32>() as i32, 0);
@@ -70,7 +69,11 @@ impl Application for App {
         )
         .expect("Failed to create program");
 
-        Self { vao, vbo, program }
+        Self {
+            vao,
+            _vbo: vbo,
+            program,
+        }
     }
 
     unsafe fn render(&mut self, ctx: &AppContext) {
@@ -78,42 +81,34 @@ impl Application for App {
         gl.clear_color(0.2, 0.3, 0.3, 1.0);
         gl.clear(COLOR_BUFFER_BIT);
 
-        gl.use_program(Some(self.program));
+        gl.use_program(Some(self.program.raw()));
 
         let green_value = (ctx.render_delta_time().sin() / 2.0) + 0.5;
 
-        let our_color = gl.get_uniform_location(self.program, "ourColor").unwrap();
+        let our_color = gl
+            .get_uniform_location(self.program.raw(), "ourColor")
+            .unwrap();
         gl.uniform_4_f32(Some(&our_color), 0.0, green_value, 0.0, 1.0);
 
         // seeing as we only have a single VAO there's no need to bind it every time,
         // but we'll do so to keep things a bit more organized
-        gl.bind_vertex_array(Some(self.vao));
+        gl.bind_vertex_array(Some(self.vao.raw()));
         gl.draw_arrays(TRIANGLES, 0, 3);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        gl.delete_program(self.program);
-
-        gl.delete_vertex_array(self.vao);
-
-        gl.delete_buffer(self.vbo);
-    }
 }
 
 fn create_program(
-    gl: &Context,
+    gl: &SharedGl,
     vertex_shader: &str,
     fragment_shader: &str,
     shader_version: &str,
-) -> Result<Program, String> {
+) -> Result<GlProgram, String> {
     let (vertex_shader, fragment_shader) = (
         format!("{}\n{}", shader_version, vertex_shader),
         format!("{}\n{}", shader_version, fragment_shader),
     );
 
-    let program = unsafe { gl.create_program().expect("Cannot create program") };
+    let program = GlProgram::new(gl).expect("Cannot create program");
 
     let (vertex, fragment) = (
         compile_shader(gl, VERTEX_SHADER, &vertex_shader)?,
@@ -121,18 +116,18 @@ fn create_program(
     );
 
     unsafe {
-        gl.attach_shader(program, vertex);
-        gl.attach_shader(program, fragment);
-        gl.link_program(program);
+        gl.attach_shader(program.raw(), vertex);
+        gl.attach_shader(program.raw(), fragment);
+        gl.link_program(program.raw());
     }
 
-    if !unsafe { gl.get_program_link_status(program) } {
-        return Err(unsafe { gl.get_program_info_log(program) });
+    if !unsafe { gl.get_program_link_status(program.raw()) } {
+        return Err(unsafe { gl.get_program_info_log(program.raw()) });
     }
 
     unsafe {
-        gl.detach_shader(program, vertex);
-        gl.detach_shader(program, fragment);
+        gl.detach_shader(program.raw(), vertex);
+        gl.detach_shader(program.raw(), fragment);
         gl.delete_shader(vertex);
         gl.delete_shader(fragment);
     }
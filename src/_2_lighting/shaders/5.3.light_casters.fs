@@ -5,29 +5,11 @@ in vec3 FragPos;
 in vec3 Normal;
 in vec2 TexCoords;
 
-struct Material {
-    sampler2D diffuse;
-    sampler2D specular;
-    float shininess;
-};
-
-struct Light {
-    vec3 position;
-    vec3 direction;
-    float cutOff;
-
-    vec3 ambient;
-    vec3 diffuse;
-    vec3 specular;
-
-    float constant;
-    float linear;
-    float quadratic;
-};
+#include "lighting.glsl"
 
 uniform vec3 viewPos;
 uniform Material material;
-uniform Light light;
+uniform SpotLight light;
 
 void main()
 {
@@ -41,24 +23,14 @@ void main()
         vec3 diffuse_color = texture(material.diffuse, TexCoords).rgb;
         vec3 specular_color = texture(material.specular, TexCoords).rgb;
 
-        // ambient
-        vec3 ambient = light.ambient * diffuse_color;
-
-        // diffuse
         vec3 norm = normalize(Normal);
-        float diff = max(dot(norm, lightDir), 0.0);
-        vec3 diffuse = light.diffuse * diff * diffuse_color;
-
-        // specular
         vec3 viewDir = normalize(viewPos - FragPos);
-        vec3 reflectDir = reflect(-lightDir, norm);
-        float spec = pow(max(dot(viewDir, reflectDir), 0.0), material.shininess);
-        // here we inverse the sampled specular color. Black becomes white and white becomes black.
-        vec3 specular = light.specular * spec * specular_color;
-
+        vec3 ambient, diffuse, specular;
+        CalcPhong(lightDir, light.ambient, light.diffuse, light.specular,
+                  norm, viewDir, diffuse_color, specular_color, material.shininess,
+                  ambient, diffuse, specular);
 
-        float distance = length(light.position - FragPos);
-        float attenuation = 1.0 / (light.constant + light.linear * distance + light.quadratic * (distance * distance));
+        float attenuation = CalcAttenuation(light.constant, light.linear, light.quadratic, light.position, FragPos);
 
         // remove attenuation from ambient, as otherwise at large distances the light would be darker inside than outside the spotlight due the ambient term in the else branch
         //        ambient  *= attenuation;
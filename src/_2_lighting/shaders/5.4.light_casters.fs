@@ -5,62 +5,32 @@ in vec3 FragPos;
 in vec3 Normal;
 in vec2 TexCoords;
 
-struct Material {
-    sampler2D diffuse;
-    sampler2D specular;
-    float shininess;
-};
-
-struct Light {
-    vec3 position;
-    vec3 direction;
-    float cutOff;
-    float outerCutOff;
-
-    vec3 ambient;
-    vec3 diffuse;
-    vec3 specular;
-
-    float constant;
-    float linear;
-    float quadratic;
-};
+#include "lighting.glsl"
 
 uniform vec3 viewPos;
 uniform Material material;
-uniform Light light;
+uniform SpotLight light;
 
 void main()
 {
     vec3 diffuse_color = texture(material.diffuse, TexCoords).rgb;
     vec3 specular_color = texture(material.specular, TexCoords).rgb;
 
-    // ambient
-    vec3 ambient = light.ambient * diffuse_color;
-
-    // diffuse
     vec3 norm = normalize(Normal);
     vec3 lightDir = normalize(light.position - FragPos);
-    float diff = max(dot(norm, lightDir), 0.0);
-    vec3 diffuse = light.diffuse * diff * diffuse_color;
-
-    // specular
     vec3 viewDir = normalize(viewPos - FragPos);
-    vec3 reflectDir = reflect(-lightDir, norm);
-    float spec = pow(max(dot(viewDir, reflectDir), 0.0), material.shininess);
-    // here we inverse the sampled specular color. Black becomes white and white becomes black.
-    vec3 specular = light.specular * spec * specular_color;
+    vec3 ambient, diffuse, specular;
+    CalcPhong(lightDir, light.ambient, light.diffuse, light.specular,
+              norm, viewDir, diffuse_color, specular_color, material.shininess,
+              ambient, diffuse, specular);
 
-    // spotlight (soft edges)
-    float theta = dot(lightDir, normalize(-light.direction));
-    float epsilon = light.cutOff - light.outerCutOff;
-    float intensity = clamp((theta - light.outerCutOff) / epsilon, 0.0, 1.0);
+    // spotlight (soft edges), the ambient term stays outside the cone
+    float intensity = CalcSpotIntensity(light, lightDir);
     diffuse *= intensity;
     specular *= intensity;
 
     // attenuation
-    float distance = length(light.position - FragPos);
-    float attenuation = 1.0 / (light.constant + light.linear * distance + light.quadratic * (distance * distance));
+    float attenuation = CalcAttenuation(light.constant, light.linear, light.quadratic, light.position, FragPos);
 
     ambient *= attenuation;
     diffuse *= attenuation;
@@ -0,0 +1,111 @@
+// shared by the light casters and multiple lights shaders, see `#include` in MyShader::load_source
+
+struct Material {
+    sampler2D diffuse;
+    sampler2D specular;
+    float shininess;
+};
+
+struct DirLight {
+    vec3 direction;
+
+    vec3 ambient;
+    vec3 diffuse;
+    vec3 specular;
+};
+
+struct PointLight {
+    vec3 position;
+
+    float constant;
+    float linear;
+    float quadratic;
+
+    vec3 ambient;
+    vec3 diffuse;
+    vec3 specular;
+};
+
+struct SpotLight {
+    vec3 position;
+    vec3 direction;
+    float cutOff;
+    float outerCutOff;
+
+    vec3 ambient;
+    vec3 diffuse;
+    vec3 specular;
+
+    float constant;
+    float linear;
+    float quadratic;
+};
+
+float CalcAttenuation(float constant, float linear, float quadratic, vec3 position, vec3 fragPos)
+{
+    float distance = length(position - fragPos);
+    return 1.0 / (constant + linear * distance + quadratic * (distance * distance));
+}
+
+// ambient, diffuse and specular of a light coming from lightDir, before attenuation
+void CalcPhong(vec3 lightDir, vec3 lightAmbient, vec3 lightDiffuse, vec3 lightSpecular,
+               vec3 normal, vec3 viewDir, vec3 diffuseColor, vec3 specularColor, float shininess,
+               out vec3 ambient, out vec3 diffuse, out vec3 specular)
+{
+    // diffuse shading
+    float diff = max(dot(normal, lightDir), 0.0);
+    // specular shading
+    vec3 reflectDir = reflect(-lightDir, normal);
+    float spec = pow(max(dot(viewDir, reflectDir), 0.0), shininess);
+    // combine results
+    ambient = lightAmbient * diffuseColor;
+    diffuse = lightDiffuse * diff * diffuseColor;
+    specular = lightSpecular * spec * specularColor;
+}
+
+// calculates the color when using a directional light.
+vec3 CalcDirLight(DirLight light, vec3 normal, vec3 viewDir,
+                  vec3 diffuseColor, vec3 specularColor, float shininess)
+{
+    vec3 ambient, diffuse, specular;
+    CalcPhong(normalize(-light.direction), light.ambient, light.diffuse, light.specular,
+              normal, viewDir, diffuseColor, specularColor, shininess,
+              ambient, diffuse, specular);
+    return ambient + diffuse + specular;
+}
+
+// calculates the color when using a point light.
+vec3 CalcPointLight(PointLight light, vec3 normal, vec3 fragPos, vec3 viewDir,
+                    vec3 diffuseColor, vec3 specularColor, float shininess)
+{
+    vec3 ambient, diffuse, specular;
+    CalcPhong(normalize(light.position - fragPos), light.ambient, light.diffuse, light.specular,
+              normal, viewDir, diffuseColor, specularColor, shininess,
+              ambient, diffuse, specular);
+    float attenuation = CalcAttenuation(light.constant, light.linear, light.quadratic,
+                                        light.position, fragPos);
+    return (ambient + diffuse + specular) * attenuation;
+}
+
+// cone factor of a spot light with soft edges, 1 inside cutOff and 0 outside outerCutOff
+float CalcSpotIntensity(SpotLight light, vec3 lightDir)
+{
+    float theta = dot(lightDir, normalize(-light.direction));
+    float epsilon = light.cutOff - light.outerCutOff;
+    return clamp((theta - light.outerCutOff) / epsilon, 0.0, 1.0);
+}
+
+// calculates the color when using a spot light.
+vec3 CalcSpotLight(SpotLight light, vec3 normal, vec3 fragPos, vec3 viewDir,
+                   vec3 diffuseColor, vec3 specularColor, float shininess)
+{
+    vec3 lightDir = normalize(light.position - fragPos);
+    vec3 ambient, diffuse, specular;
+    CalcPhong(lightDir, light.ambient, light.diffuse, light.specular,
+              normal, viewDir, diffuseColor, specularColor, shininess,
+              ambient, diffuse, specular);
+    float attenuation = CalcAttenuation(light.constant, light.linear, light.quadratic,
+                                        light.position, fragPos);
+    float intensity = CalcSpotIntensity(light, lightDir);
+    return (ambient + diffuse + specular) * attenuation * intensity;
+}
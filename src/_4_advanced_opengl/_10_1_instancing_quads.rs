@@ -1,4 +1,5 @@
 use crate::camera::Camera;
+use crate::gl_object::{GlBuffer, GlVertexArray};
 use crate::shader::MyShader;
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use glow::*;
@@ -28,9 +29,9 @@ const QUAD_VERTICES: [f32; 30] = [
 ];
 
 struct App {
-    instance_vbo: Buffer,
-    quad_vbo: Buffer,
-    quad_vao: VertexArray,
+    _instance_vbo: GlBuffer,
+    _quad_vbo: GlBuffer,
+    quad_vao: GlVertexArray,
 
     shader: MyShader,
     camera: Camera,
@@ -65,26 +66,24 @@ impl Application for App {
 
         // store instance data in an array buffer
         // --------------------------------------
-        let instance_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(instance_vbo));
+        let instance_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(instance_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&translations),
             STATIC_DRAW,
         );
 
-        let quad_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(quad_vbo));
+        let quad_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(quad_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&QUAD_VERTICES),
             STATIC_DRAW,
         );
 
-        let quad_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(quad_vao));
+        let quad_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(quad_vao.raw()));
         gl.vertex_attrib_pointer_f32(0, 2, FLOAT, false, 5 * size_of::<f32>() as i32, 0);
         gl.enable_vertex_attrib_array(0);
         gl.vertex_attrib_pointer_f32(
@@ -98,7 +97,7 @@ impl Application for App {
         gl.enable_vertex_attrib_array(1);
         // also set instance data
         let stride = 2 * size_of::<f32>() as i32;
-        gl.bind_buffer(ARRAY_BUFFER, Some(instance_vbo)); // this attribute comes from a different vertex buffer
+        gl.bind_buffer(ARRAY_BUFFER, Some(instance_vbo.raw())); // this attribute comes from a different vertex buffer
         gl.vertex_attrib_pointer_f32(2, 2, FLOAT, false, stride, 0);
         gl.enable_vertex_attrib_array(2);
         gl.vertex_attrib_divisor(2, 1); // tell OpenGL this is an instanced vertex attribute.
@@ -107,8 +106,8 @@ impl Application for App {
         gl.bind_vertex_array(None);
 
         Self {
-            instance_vbo,
-            quad_vbo,
+            _instance_vbo: instance_vbo,
+            _quad_vbo: quad_vbo,
             quad_vao,
             shader,
             camera,
@@ -122,7 +121,7 @@ impl Application for App {
         gl.clear(COLOR_BUFFER_BIT | DEPTH_BUFFER_BIT);
 
         self.shader.use_shader(gl);
-        gl.bind_vertex_array(Some(self.quad_vao));
+        gl.bind_vertex_array(Some(self.quad_vao.raw()));
         gl.draw_arrays_instanced(TRIANGLES, 0, 6, 100); // 100 triangles of 6 vertices each
 
         gl.bind_vertex_array(None);
@@ -132,14 +131,4 @@ impl Application for App {
         self.camera.process_keyboard_with_input(input);
         self.camera.process_mouse_with_input(input, true);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.shader.delete(gl);
-
-        gl.delete_vertex_array(self.quad_vao);
-        gl.delete_buffer(self.quad_vbo);
-        gl.delete_buffer(self.instance_vbo);
-    }
 }
@@ -2,10 +2,11 @@ use crate::camera::Camera;
 use crate::model::Model;
 use crate::resources;
 use crate::shader::MyShader;
-use crate::window::{run, AppContext, Application, WindowInitInfo};
+use crate::window::{launch_seed, run, AppContext, Application, WindowInitInfo};
 use glow::*;
 use nalgebra_glm as glm;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use winit_input_helper::WinitInputHelper;
 
 pub async unsafe fn main_4_10_2() {
@@ -102,7 +103,7 @@ impl Application for App {
     fn ui(
         &mut self,
         state: &crate::window::AppState,
-        _gl_ctx: &crate::window::GLContext,
+        gl_ctx: &crate::window::GLContext,
         egui_ctx: &egui::Context,
     ) {
         egui::Window::new("Info").show(egui_ctx, |ui| {
@@ -118,29 +119,24 @@ impl Application for App {
                 self.model_matrices = generate_matrices(amount as usize);
             }
         });
+        crate::shader_reflection::shader_window(&gl_ctx.gl, egui_ctx);
     }
 
     unsafe fn process_input(&mut self, _ctx: &AppContext, input: &WinitInputHelper) {
         self.camera.process_keyboard_with_input(input);
         self.camera.process_mouse_with_input(input, true);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.shader.delete(gl);
-
-        self.rock.delete(gl);
-        self.planet.delete(gl);
-    }
 }
 
 pub fn generate_matrices(amount: usize) -> Vec<glm::Mat4> {
     let mut model_matrices = Vec::with_capacity(amount);
     let radius = 50.0;
     let offset = 2.5;
-    // initialize random seed
-    let mut rng = rand::thread_rng();
+    // initialize random seed, fixed when launched with one so the frames can be compared
+    let mut rng = match launch_seed() {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
 
     for i in 0..amount {
         let mut model = glm::Mat4::identity();
@@ -1,11 +1,13 @@
 use crate::camera::Camera;
+use crate::gl_object::GlBuffer;
 use crate::model::Model;
 use crate::resources;
 use crate::shader::MyShader;
-use crate::window::{run, AppContext, Application, WindowInitInfo};
+use crate::window::{launch_seed, run, AppContext, Application, SharedGl, WindowInitInfo};
 use glow::*;
 use nalgebra_glm as glm;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use winit_input_helper::WinitInputHelper;
 
 pub async unsafe fn main_4_10_3() {
@@ -21,7 +23,7 @@ struct App {
     rock: Model,
     planet: Model,
     model_matrices: Vec<glm::Mat4>,
-    buffer: Buffer,
+    _buffer: GlBuffer,
 
     asteroid_shader: MyShader,
     planet_shader: MyShader,
@@ -71,7 +73,7 @@ impl Application for App {
             rock,
             planet,
             model_matrices,
-            buffer,
+            _buffer: buffer,
             asteroid_shader,
             planet_shader,
             camera,
@@ -111,7 +113,7 @@ impl Application for App {
         self.asteroid_shader.set_int(gl, "texture_diffuse1", 0);
         gl.bind_texture(TEXTURE_2D, Some(self.rock.materials[0].textures[0].raw()));
         for mesh in &self.rock.meshes {
-            gl.bind_vertex_array(Some(mesh.vao));
+            gl.bind_vertex_array(Some(mesh.vao.raw()));
             gl.draw_elements_instanced(
                 TRIANGLES,
                 mesh.indices.len() as i32,
@@ -141,37 +143,24 @@ impl Application for App {
             );
             if amount != self.model_matrices.len() as f32 {
                 let gl = &gl_ctx.gl;
-                unsafe {
-                    gl.delete_buffer(self.buffer);
-                }
                 self.model_matrices = generate_matrices(amount as usize);
-                self.buffer = unsafe { create_buffer(gl, &self.rock, &self.model_matrices) };
+                self._buffer = unsafe { create_buffer(gl, &self.rock, &self.model_matrices) };
             }
         });
+        crate::shader_reflection::shader_window(&gl_ctx.gl, egui_ctx);
     }
 
     unsafe fn process_input(&mut self, _ctx: &AppContext, input: &WinitInputHelper) {
         self.camera.process_keyboard_with_input(input);
         self.camera.process_mouse_with_input(input, true);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.asteroid_shader.delete(gl);
-        self.planet_shader.delete(gl);
-        gl.delete_buffer(self.buffer);
-
-        self.rock.delete(gl);
-        self.planet.delete(gl);
-    }
 }
 
-unsafe fn create_buffer(gl: &Context, rock: &Model, model_matrices: &[glm::Mat4]) -> Buffer {
+unsafe fn create_buffer(gl: &SharedGl, rock: &Model, model_matrices: &[glm::Mat4]) -> GlBuffer {
     // configure instanced array
     // -------------------------
-    let buffer = gl.create_buffer().expect("Create buffer failed");
-    gl.bind_buffer(ARRAY_BUFFER, Some(buffer));
+    let buffer = GlBuffer::new(gl).expect("Create buffer failed");
+    gl.bind_buffer(ARRAY_BUFFER, Some(buffer.raw()));
     gl.buffer_data_u8_slice(
         ARRAY_BUFFER,
         bytemuck::cast_slice(model_matrices),
@@ -183,7 +172,7 @@ unsafe fn create_buffer(gl: &Context, rock: &Model, model_matrices: &[glm::Mat4]
     // normally you'd want to do this in a more organized fashion, but for learning purposes this will do.
     // -----------------------------------------------------------------------------------------------------------------------------------
     for mesh in &rock.meshes {
-        let vao = mesh.vao;
+        let vao = mesh.vao.raw();
         gl.bind_vertex_array(Some(vao));
         // 4 times vec4
         let stride = std::mem::size_of::<glm::Mat4>() as i32;
@@ -217,8 +206,11 @@ pub fn generate_matrices(amount: usize) -> Vec<glm::Mat4> {
     let mut model_matrices = Vec::with_capacity(amount);
     let radius = 150.0;
     let offset = 25.0;
-    // initialize random seed
-    let mut rng = rand::thread_rng();
+    // initialize random seed, fixed when launched with one so the frames can be compared
+    let mut rng = match launch_seed() {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
 
     for i in 0..amount {
         let mut model = glm::Mat4::identity();
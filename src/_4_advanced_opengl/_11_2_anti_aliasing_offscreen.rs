@@ -0,0 +1,237 @@
+use crate::camera::Camera;
+use crate::gl_object::{GlBuffer, GlVertexArray};
+use crate::render_target::{ColorFormat, DepthAttachment, RenderTarget};
+use crate::shader::MyShader;
+use crate::window::{run, AppContext, Application, WindowInitInfo};
+use glow::*;
+use nalgebra_glm as glm;
+use std::mem::size_of;
+use winit_input_helper::WinitInputHelper;
+
+pub async unsafe fn main_4_11_2() {
+    let init_info = WindowInitInfo::builder()
+        .title("Anti Aliasing Offscreen".to_string())
+        .build();
+    unsafe {
+        run::<App>(init_info).await;
+    }
+}
+
+#[rustfmt::skip]
+const VERTICES: [f32; 108] = [
+    // pos           
+    -0.5, -0.5, -0.5,
+    0.5, -0.5, -0.5,
+    0.5,  0.5, -0.5,
+    0.5,  0.5, -0.5,
+    -0.5,  0.5, -0.5,
+    -0.5, -0.5, -0.5,
+
+    -0.5, -0.5,  0.5,
+    0.5, -0.5,  0.5,
+    0.5,  0.5,  0.5,
+    0.5,  0.5,  0.5,
+    -0.5,  0.5,  0.5,
+    -0.5, -0.5,  0.5,
+
+    -0.5,  0.5,  0.5,
+    -0.5,  0.5, -0.5,
+    -0.5, -0.5, -0.5,
+    -0.5, -0.5, -0.5,
+    -0.5, -0.5,  0.5,
+    -0.5,  0.5,  0.5,
+
+    0.5,  0.5,  0.5,
+    0.5,  0.5, -0.5,
+    0.5, -0.5, -0.5,
+    0.5, -0.5, -0.5,
+    0.5, -0.5,  0.5,
+    0.5,  0.5,  0.5,
+
+    -0.5, -0.5, -0.5,
+    0.5, -0.5, -0.5,
+    0.5, -0.5,  0.5,
+    0.5, -0.5,  0.5,
+    -0.5, -0.5,  0.5,
+    -0.5, -0.5, -0.5,
+
+    -0.5,  0.5, -0.5,
+    0.5,  0.5, -0.5,
+    0.5,  0.5,  0.5,
+    0.5,  0.5,  0.5,
+    -0.5,  0.5,  0.5,
+    -0.5,  0.5, -0.5,
+];
+
+#[rustfmt::skip]
+const QUAD_VERTICES: [f32; 24] = [
+    // positions    texture Coords
+    -1.0,  1.0,  0.0, 1.0,
+    -1.0, -1.0,  0.0, 0.0,
+    1.0, -1.0,  1.0, 0.0,
+
+    -1.0,  1.0,  0.0, 1.0,
+    1.0, -1.0,  1.0, 0.0,
+    1.0,  1.0,  1.0, 1.0
+];
+
+struct App {
+    cube_vao: GlVertexArray,
+    _cube_vbo: GlBuffer,
+    quad_vao: GlVertexArray,
+    _quad_vbo: GlBuffer,
+    /// the scene is drawn into multisampled renderbuffers
+    multisampled_framebuffer: RenderTarget,
+    /// and resolved into a texture to draw on a screen quad
+    intermediate_framebuffer: RenderTarget,
+    shader: MyShader,
+    screen_shader: MyShader,
+    camera: Camera,
+}
+
+impl Application for App {
+    async unsafe fn new(ctx: &AppContext) -> Self {
+        let gl = ctx.gl();
+        let shader = MyShader::new_from_source(
+            gl,
+            include_str!("./shaders/_9_3_default.vs"),
+            include_str!("./shaders/_8_1_green.fs"),
+            Some(ctx.suggested_shader_version()),
+        )
+        .expect("Failed to create program");
+        let screen_shader = MyShader::new_from_source(
+            gl,
+            include_str!("./shaders/_5_1_framebuffers_screen.vs"),
+            include_str!("./shaders/_5_1_framebuffers_screen.fs"),
+            Some(ctx.suggested_shader_version()),
+        )
+        .expect("Failed to create program");
+
+        let camera = Camera::new_with_position(glm::vec3(0.0, 0.0, 3.0));
+
+        gl.enable(DEPTH_TEST);
+
+        let cube_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(cube_vbo.raw()));
+        gl.buffer_data_u8_slice(ARRAY_BUFFER, bytemuck::cast_slice(&VERTICES), STATIC_DRAW);
+
+        let cube_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(cube_vao.raw()));
+        gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, 3 * size_of::<f32>() as i32, 0);
+        gl.enable_vertex_attrib_array(0);
+
+        // screen quad vao
+        let quad_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(quad_vbo.raw()));
+        gl.buffer_data_u8_slice(
+            ARRAY_BUFFER,
+            bytemuck::cast_slice(&QUAD_VERTICES),
+            STATIC_DRAW,
+        );
+
+        let quad_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(quad_vao.raw()));
+        gl.vertex_attrib_pointer_f32(0, 2, FLOAT, false, 4 * size_of::<f32>() as i32, 0);
+        gl.enable_vertex_attrib_array(0);
+        gl.vertex_attrib_pointer_f32(
+            1,
+            2,
+            FLOAT,
+            false,
+            4 * size_of::<f32>() as i32,
+            (2 * size_of::<f32>()) as i32,
+        );
+        gl.enable_vertex_attrib_array(1);
+
+        gl.bind_buffer(ARRAY_BUFFER, None);
+        gl.bind_vertex_array(None);
+
+        // configure MSAA framebuffer
+        // --------------------------
+        let multisampled_framebuffer = RenderTarget::builder(ctx.width(), ctx.height())
+            .color(ColorFormat::RGBA8)
+            .depth(DepthAttachment::DepthStencil)
+            .samples(4)
+            .build(gl)
+            .expect("Failed to create multisampled framebuffer");
+        // configure second post-processing framebuffer
+        let intermediate_framebuffer = RenderTarget::builder(ctx.width(), ctx.height())
+            .color(ColorFormat::RGBA8)
+            .build(gl)
+            .expect("Failed to create intermediate framebuffer");
+
+        screen_shader.use_shader(gl);
+        screen_shader.set_int(gl, "screenTexture", 0);
+
+        Self {
+            cube_vao,
+            _cube_vbo: cube_vbo,
+            quad_vao,
+            _quad_vbo: quad_vbo,
+            multisampled_framebuffer,
+            intermediate_framebuffer,
+            shader,
+            screen_shader,
+            camera,
+        }
+    }
+
+    unsafe fn render(&mut self, ctx: &AppContext) {
+        let gl = ctx.gl();
+
+        // 1. draw scene as normal in multisampled buffers
+        self.multisampled_framebuffer.bind(gl);
+        gl.enable(DEPTH_TEST);
+        gl.clear_color(0.1, 0.1, 0.1, 1.0);
+        gl.clear(COLOR_BUFFER_BIT | DEPTH_BUFFER_BIT);
+
+        self.shader.use_shader(gl);
+
+        let projection = glm::perspective(
+            ctx.width() as f32 / ctx.height() as f32,
+            self.camera.zoom().to_radians(),
+            0.1,
+            100.0,
+        );
+        let view = self.camera.view_matrix();
+        self.shader.set_mat4(gl, "projection", &projection);
+        self.shader.set_mat4(gl, "view", &view);
+        self.shader.set_mat4(gl, "model", &glm::Mat4::identity());
+
+        gl.bind_vertex_array(Some(self.cube_vao.raw()));
+        gl.draw_arrays(TRIANGLES, 0, 36);
+
+        // 2. now blit multisampled buffer(s) to normal colorbuffer of intermediate FBO. Image is stored in screenTexture
+        self.multisampled_framebuffer
+            .blit_to(gl, &self.intermediate_framebuffer);
+
+        // 3. now render quad with scene's visuals as its texture image
+        gl.bind_framebuffer(FRAMEBUFFER, None);
+        gl.clear_color(1.0, 1.0, 1.0, 1.0);
+        gl.clear(COLOR_BUFFER_BIT);
+        gl.disable(DEPTH_TEST);
+
+        // draw Screen quad
+        self.screen_shader.use_shader(gl);
+        gl.bind_vertex_array(Some(self.quad_vao.raw()));
+        gl.active_texture(TEXTURE0);
+        gl.bind_texture(TEXTURE_2D, self.intermediate_framebuffer.color_texture(0));
+        gl.draw_arrays(TRIANGLES, 0, 6);
+    }
+
+    unsafe fn resize(&mut self, ctx: &AppContext, width: u32, height: u32) {
+        let gl = ctx.gl();
+        gl.viewport(0, 0, width as i32, height as i32);
+        self.multisampled_framebuffer
+            .resize(gl, width, height)
+            .expect("Failed to resize multisampled framebuffer");
+        self.intermediate_framebuffer
+            .resize(gl, width, height)
+            .expect("Failed to resize intermediate framebuffer");
+    }
+
+    unsafe fn process_input(&mut self, _ctx: &AppContext, input: &WinitInputHelper) {
+        self.camera.process_keyboard_with_input(input);
+        self.camera.process_mouse_with_input(input, true);
+    }
+}
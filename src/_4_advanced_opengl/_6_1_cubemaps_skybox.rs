@@ -1,10 +1,10 @@
 use crate::camera::Camera;
-use crate::resources::load_binary;
+use crate::gl_object::{GlBuffer, GlVertexArray};
 use crate::shader::MyShader;
+use crate::texture::CubemapTexture;
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use crate::{resources, texture};
 use glow::*;
-use image::GenericImageView;
 use nalgebra_glm as glm;
 use std::mem::size_of;
 use winit_input_helper::WinitInputHelper;
@@ -110,13 +110,13 @@ const SKYBOX_VERTICES: [f32; 108] = [
 ];
 
 struct App {
-    cube_vbo: Buffer,
-    cube_vao: VertexArray,
+    _cube_vbo: GlBuffer,
+    cube_vao: GlVertexArray,
     cube_texture: texture::Texture,
 
-    skybox_vbo: Buffer,
-    skybox_vao: VertexArray,
-    skybox_texture: Texture,
+    _skybox_vbo: GlBuffer,
+    skybox_vao: GlVertexArray,
+    skybox_texture: CubemapTexture,
 
     shader: MyShader,
     skybox_shader: MyShader,
@@ -148,14 +148,12 @@ impl Application for App {
         gl.enable(DEPTH_TEST);
 
         //  cube vao
-        let cube_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(cube_vbo));
+        let cube_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(cube_vbo.raw()));
         gl.buffer_data_u8_slice(ARRAY_BUFFER, bytemuck::cast_slice(&VERTICES), STATIC_DRAW);
 
-        let cube_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(cube_vao));
+        let cube_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(cube_vao.raw()));
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, 5 * size_of::<f32>() as i32, 0);
         gl.enable_vertex_attrib_array(0);
         gl.vertex_attrib_pointer_f32(
@@ -170,18 +168,16 @@ impl Application for App {
         gl.bind_vertex_array(None);
 
         // skybox vao
-        let skybox_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(skybox_vbo));
+        let skybox_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(skybox_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&SKYBOX_VERTICES),
             STATIC_DRAW,
         );
 
-        let skybox_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(skybox_vao));
+        let skybox_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(skybox_vao.raw()));
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, 3 * size_of::<f32>() as i32, 0);
         gl.enable_vertex_attrib_array(0);
 
@@ -191,7 +187,7 @@ impl Application for App {
         let cube_texture = resources::load_texture(gl, "textures/container.jpg")
             .await
             .expect("Failed to load texture");
-        let skybox_texture = load_cubemap(
+        let skybox_texture = resources::load_cubemap(
             gl,
             &[
                 "textures/skybox/right.jpg",
@@ -202,7 +198,8 @@ impl Application for App {
                 "textures/skybox/back.jpg",
             ],
         )
-        .await;
+        .await
+        .expect("Failed to load cubemap");
 
         shader.use_shader(gl);
         shader.set_int(gl, "texture1", 0);
@@ -211,10 +208,10 @@ impl Application for App {
         skybox_shader.set_int(gl, "skybox", 0);
 
         Self {
-            cube_vbo,
+            _cube_vbo: cube_vbo,
             cube_vao,
             cube_texture,
-            skybox_vbo,
+            _skybox_vbo: skybox_vbo,
             skybox_vao,
             skybox_texture,
             shader,
@@ -242,7 +239,7 @@ impl Application for App {
         self.shader.set_mat4(gl, "view", &view);
 
         // cubes
-        gl.bind_vertex_array(Some(self.cube_vao));
+        gl.bind_vertex_array(Some(self.cube_vao.raw()));
         self.cube_texture.bind(gl, 0);
 
         let model = glm::translate(&glm::Mat4::identity(), &glm::vec3(-1.0, 0.0, -1.0));
@@ -261,9 +258,8 @@ impl Application for App {
         self.skybox_shader.set_mat4(gl, "view", &view);
         self.skybox_shader.set_mat4(gl, "projection", &projection);
         // skybox cube
-        gl.bind_vertex_array(Some(self.skybox_vao));
-        gl.active_texture(TEXTURE0);
-        gl.bind_texture(TEXTURE_CUBE_MAP, Some(self.skybox_texture));
+        gl.bind_vertex_array(Some(self.skybox_vao.raw()));
+        self.skybox_texture.bind(gl, 0);
         gl.draw_arrays(TRIANGLES, 0, 36);
 
         gl.bind_vertex_array(None);
@@ -274,48 +270,4 @@ impl Application for App {
         self.camera.process_keyboard_with_input(input);
         self.camera.process_mouse_with_input(input, true);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.shader.delete(gl);
-
-        gl.delete_buffer(self.cube_vbo);
-        gl.delete_vertex_array(self.cube_vao);
-        self.cube_texture.delete(gl);
-
-        gl.delete_buffer(self.skybox_vbo);
-        gl.delete_vertex_array(self.skybox_vao);
-        gl.delete_texture(self.skybox_texture);
-    }
-}
-
-async unsafe fn load_cubemap(gl: &Context, faces: &[&str]) -> Texture {
-    let texture = gl.create_texture().expect("Failed to create texture");
-    gl.bind_texture(TEXTURE_CUBE_MAP, Some(texture));
-
-    for (i, face) in faces.iter().enumerate() {
-        let data = load_binary(face).await.expect("Failed to load texture");
-        let img = image::load_from_memory(&data).expect("Failed to load image");
-        let (width, height) = img.dimensions();
-        let data = img.to_rgb8();
-        gl.tex_image_2d(
-            TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
-            0,
-            RGB as i32,
-            width as i32,
-            height as i32,
-            0,
-            RGB,
-            UNSIGNED_BYTE,
-            Some(&data),
-        );
-    }
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_MIN_FILTER, LINEAR as i32);
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_MAG_FILTER, LINEAR as i32);
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_S, CLAMP_TO_EDGE as i32);
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_T, CLAMP_TO_EDGE as i32);
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_R, CLAMP_TO_EDGE as i32);
-
-    texture
 }
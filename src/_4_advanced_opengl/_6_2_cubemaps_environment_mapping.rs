@@ -1,9 +1,10 @@
 use crate::camera::Camera;
-use crate::resources::load_binary;
+use crate::gl_object::{GlBuffer, GlVertexArray};
+use crate::resources;
 use crate::shader::MyShader;
+use crate::texture::CubemapTexture;
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use glow::*;
-use image::GenericImageView;
 use nalgebra_glm as glm;
 use std::mem::size_of;
 use winit_input_helper::WinitInputHelper;
@@ -109,12 +110,12 @@ const SKYBOX_VERTICES: [f32; 108] = [
 ];
 
 struct App {
-    cube_vbo: Buffer,
-    cube_vao: VertexArray,
+    _cube_vbo: GlBuffer,
+    cube_vao: GlVertexArray,
 
-    skybox_vbo: Buffer,
-    skybox_vao: VertexArray,
-    skybox_texture: Texture,
+    _skybox_vbo: GlBuffer,
+    skybox_vao: GlVertexArray,
+    skybox_texture: CubemapTexture,
 
     shader: MyShader,
     skybox_shader: MyShader,
@@ -146,14 +147,12 @@ impl Application for App {
         gl.enable(DEPTH_TEST);
 
         //  cube vao
-        let cube_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(cube_vbo));
+        let cube_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(cube_vbo.raw()));
         gl.buffer_data_u8_slice(ARRAY_BUFFER, bytemuck::cast_slice(&VERTICES), STATIC_DRAW);
 
-        let cube_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(cube_vao));
+        let cube_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(cube_vao.raw()));
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, 6 * size_of::<f32>() as i32, 0);
         gl.enable_vertex_attrib_array(0);
         gl.vertex_attrib_pointer_f32(
@@ -168,25 +167,23 @@ impl Application for App {
         gl.bind_vertex_array(None);
 
         // skybox vao
-        let skybox_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(skybox_vbo));
+        let skybox_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(skybox_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&SKYBOX_VERTICES),
             STATIC_DRAW,
         );
 
-        let skybox_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(skybox_vao));
+        let skybox_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(skybox_vao.raw()));
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, 3 * size_of::<f32>() as i32, 0);
         gl.enable_vertex_attrib_array(0);
 
         gl.bind_vertex_array(None);
 
         // load texture
-        let skybox_texture = load_cubemap(
+        let skybox_texture = resources::load_cubemap(
             gl,
             &[
                 "textures/skybox/right.jpg",
@@ -197,7 +194,8 @@ impl Application for App {
                 "textures/skybox/back.jpg",
             ],
         )
-        .await;
+        .await
+        .expect("Failed to load cubemap");
 
         shader.use_shader(gl);
         shader.set_int(gl, "skybox", 0);
@@ -206,9 +204,9 @@ impl Application for App {
         skybox_shader.set_int(gl, "skybox", 0);
 
         Self {
-            cube_vbo,
+            _cube_vbo: cube_vbo,
             cube_vao,
-            skybox_vbo,
+            _skybox_vbo: skybox_vbo,
             skybox_vao,
             skybox_texture,
             shader,
@@ -240,9 +238,8 @@ impl Application for App {
             .set_vec3(gl, "cameraPos", &self.camera.position());
 
         // cubes
-        gl.bind_vertex_array(Some(self.cube_vao));
-        gl.active_texture(TEXTURE0);
-        gl.bind_texture(TEXTURE_CUBE_MAP, Some(self.skybox_texture));
+        gl.bind_vertex_array(Some(self.cube_vao.raw()));
+        self.skybox_texture.bind(gl, 0);
 
         gl.draw_arrays(TRIANGLES, 0, 36);
 
@@ -254,9 +251,8 @@ impl Application for App {
         self.skybox_shader.set_mat4(gl, "view", &view);
         self.skybox_shader.set_mat4(gl, "projection", &projection);
         // skybox cube
-        gl.bind_vertex_array(Some(self.skybox_vao));
-        gl.active_texture(TEXTURE0);
-        gl.bind_texture(TEXTURE_CUBE_MAP, Some(self.skybox_texture));
+        gl.bind_vertex_array(Some(self.skybox_vao.raw()));
+        self.skybox_texture.bind(gl, 0);
         gl.draw_arrays(TRIANGLES, 0, 36);
 
         gl.bind_vertex_array(None);
@@ -267,47 +263,4 @@ impl Application for App {
         self.camera.process_keyboard_with_input(input);
         self.camera.process_mouse_with_input(input, true);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.shader.delete(gl);
-
-        gl.delete_buffer(self.cube_vbo);
-        gl.delete_vertex_array(self.cube_vao);
-
-        gl.delete_buffer(self.skybox_vbo);
-        gl.delete_vertex_array(self.skybox_vao);
-        gl.delete_texture(self.skybox_texture);
-    }
-}
-
-async unsafe fn load_cubemap(gl: &Context, faces: &[&str]) -> Texture {
-    let texture = gl.create_texture().expect("Failed to create texture");
-    gl.bind_texture(TEXTURE_CUBE_MAP, Some(texture));
-
-    for (i, face) in faces.iter().enumerate() {
-        let data = load_binary(face).await.expect("Failed to load texture");
-        let img = image::load_from_memory(&data).expect("Failed to load image");
-        let (width, height) = img.dimensions();
-        let data = img.to_rgb8();
-        gl.tex_image_2d(
-            TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
-            0,
-            RGB as i32,
-            width as i32,
-            height as i32,
-            0,
-            RGB,
-            UNSIGNED_BYTE,
-            Some(&data),
-        );
-    }
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_MIN_FILTER, LINEAR as i32);
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_MAG_FILTER, LINEAR as i32);
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_S, CLAMP_TO_EDGE as i32);
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_T, CLAMP_TO_EDGE as i32);
-    gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_R, CLAMP_TO_EDGE as i32);
-
-    texture
 }
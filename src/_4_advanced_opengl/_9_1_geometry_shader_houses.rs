@@ -1,4 +1,5 @@
 use crate::camera::Camera;
+use crate::gl_object::{GlBuffer, GlVertexArray};
 use crate::shader::MyShader;
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use glow::*;
@@ -25,8 +26,8 @@ const POINTS: [f32; 20] = [
 ];
 
 struct App {
-    vbo: Buffer,
-    vao: VertexArray,
+    _vbo: GlBuffer,
+    vao: GlVertexArray,
 
     shader: MyShader,
     camera: Camera,
@@ -36,9 +37,6 @@ impl Application for App {
     async unsafe fn new(ctx: &AppContext) -> Self {
         let gl = ctx.gl();
 
-        #[cfg(target_arch = "wasm32")]
-        log::error!("WebGL2 doesn't support geometry shaders");
-
         let shader = MyShader::new_with_geometry_from_source(
             gl,
             include_str!("shaders/_9_1_geometry_shader.vs"),
@@ -52,14 +50,12 @@ impl Application for App {
 
         gl.enable(DEPTH_TEST);
 
-        let vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(vbo));
+        let vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(vbo.raw()));
         gl.buffer_data_u8_slice(ARRAY_BUFFER, bytemuck::cast_slice(&POINTS), STATIC_DRAW);
 
-        let vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(vao));
+        let vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(vao.raw()));
         gl.vertex_attrib_pointer_f32(0, 2, FLOAT, false, 5 * size_of::<f32>() as i32, 0);
         gl.enable_vertex_attrib_array(0);
         gl.vertex_attrib_pointer_f32(
@@ -75,7 +71,7 @@ impl Application for App {
         gl.bind_vertex_array(None);
 
         Self {
-            vbo,
+            _vbo: vbo,
             vao,
             shader,
             camera,
@@ -89,7 +85,7 @@ impl Application for App {
         gl.clear(COLOR_BUFFER_BIT | DEPTH_BUFFER_BIT);
 
         self.shader.use_shader(gl);
-        gl.bind_vertex_array(Some(self.vao));
+        gl.bind_vertex_array(Some(self.vao.raw()));
         gl.draw_arrays(glow::POINTS, 0, 4);
 
         gl.bind_vertex_array(None);
@@ -100,13 +96,4 @@ impl Application for App {
         self.camera.process_keyboard_with_input(input);
         self.camera.process_mouse_with_input(input, true);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.shader.delete(gl);
-
-        gl.delete_buffer(self.vbo);
-        gl.delete_vertex_array(self.vao);
-    }
 }
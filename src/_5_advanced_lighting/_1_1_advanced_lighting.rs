@@ -1,4 +1,5 @@
 use crate::camera::Camera;
+use crate::gl_object::{GlBuffer, GlVertexArray};
 use crate::shader::MyShader;
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use crate::{resources, texture};
@@ -31,8 +32,8 @@ const PLANE_VERTICES: [f32; 48] = [
 const LIGHT_POS: glm::Vec3 = glm::Vec3::new(0.0, 0.0, 0.0);
 
 struct App {
-    plane_vao: VertexArray,
-    plane_vbo: Buffer,
+    plane_vao: GlVertexArray,
+    _plane_vbo: GlBuffer,
     floor_texture: texture::Texture,
     shader: MyShader,
     camera: Camera,
@@ -71,18 +72,16 @@ impl Application for App {
         gl.enable(BLEND);
         gl.blend_func(SRC_ALPHA, ONE_MINUS_SRC_ALPHA);
 
-        let plane_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(plane_vbo));
+        let plane_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(plane_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&PLANE_VERTICES),
             STATIC_DRAW,
         );
 
-        let plane_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(plane_vao));
+        let plane_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(plane_vao.raw()));
         let stride = 8 * size_of::<f32>() as i32;
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, stride, 0);
         gl.enable_vertex_attrib_array(0);
@@ -101,7 +100,7 @@ impl Application for App {
 
         Self {
             plane_vao,
-            plane_vbo,
+            _plane_vbo: plane_vbo,
             floor_texture,
             shader,
             camera,
@@ -133,7 +132,7 @@ impl Application for App {
             .set_int(gl, "blinn", if self.use_blinn { 1 } else { 0 });
 
         // floor
-        gl.bind_vertex_array(Some(self.plane_vao));
+        gl.bind_vertex_array(Some(self.plane_vao.raw()));
         self.floor_texture.bind(gl, 0);
         gl.draw_arrays(TRIANGLES, 0, 6);
 
@@ -145,7 +144,7 @@ impl Application for App {
     fn ui(
         &mut self,
         state: &crate::window::AppState,
-        _gl_ctx: &crate::window::GLContext,
+        gl_ctx: &crate::window::GLContext,
         egui_ctx: &egui::Context,
     ) {
         egui::Window::new("Info").show(egui_ctx, |ui| {
@@ -153,6 +152,7 @@ impl Application for App {
             ui.label("Press Space to enable blinn");
             ui.label(format!("Blinn: {}", self.use_blinn));
         });
+        crate::shader_reflection::shader_window(&gl_ctx.gl, egui_ctx);
     }
 
     unsafe fn process_input(&mut self, _ctx: &AppContext, input: &WinitInputHelper) {
@@ -161,13 +161,4 @@ impl Application for App {
 
         self.use_blinn = input.key_held(winit::keyboard::KeyCode::Space);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.shader.delete(gl);
-
-        gl.delete_vertex_array(self.plane_vao);
-        gl.delete_buffer(self.plane_vbo);
-    }
 }
@@ -1,9 +1,10 @@
 use crate::camera::Camera;
-use crate::resources::load_binary;
+use crate::gl_object::{GlBuffer, GlVertexArray};
+use crate::resources::load_texture_with_options;
 use crate::shader::MyShader;
+use crate::texture::{Texture, TextureOptions};
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use glow::*;
-use image::GenericImageView;
 use nalgebra_glm as glm;
 use std::mem::size_of;
 use winit_input_helper::WinitInputHelper;
@@ -44,8 +45,8 @@ const LIGHT_COLORS: [glm::Vec3; 4] = [
 ];
 
 struct App {
-    plane_vao: VertexArray,
-    plane_vbo: Buffer,
+    plane_vao: GlVertexArray,
+    _plane_vbo: GlBuffer,
     floor_texture: Texture,
     floor_texture_gamma_corrected: Texture,
     shader: MyShader,
@@ -72,18 +73,16 @@ impl Application for App {
         gl.enable(BLEND);
         gl.blend_func(SRC_ALPHA, ONE_MINUS_SRC_ALPHA);
 
-        let plane_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(plane_vbo));
+        let plane_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(plane_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&PLANE_VERTICES),
             STATIC_DRAW,
         );
 
-        let plane_vao = gl
-            .create_vertex_array()
-            .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(plane_vao));
+        let plane_vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        gl.bind_vertex_array(Some(plane_vao.raw()));
         let stride = 8 * size_of::<f32>() as i32;
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, stride, 0);
         gl.enable_vertex_attrib_array(0);
@@ -96,19 +95,24 @@ impl Application for App {
 
         // load textures
         // -------------
-        let floor_texture = load_texture(gl, "textures/wood.png", false)
-            .await
-            .expect("Failed to load texture");
-        let floor_texture_gamma_corrected = load_texture(gl, "textures/wood.png", true)
-            .await
-            .expect("Failed to load texture");
+        let floor_texture =
+            load_texture_with_options(gl, "textures/wood.png", &TextureOptions::default())
+                .await
+                .expect("Failed to load texture");
+        let floor_texture_gamma_corrected = load_texture_with_options(
+            gl,
+            "textures/wood.png",
+            &TextureOptions::default().srgb(true),
+        )
+        .await
+        .expect("Failed to load texture");
 
         shader.use_shader(gl);
         shader.set_int(gl, "floorTexture", 0);
 
         Self {
             plane_vao,
-            plane_vbo,
+            _plane_vbo: plane_vbo,
             floor_texture,
             floor_texture_gamma_corrected,
             shader,
@@ -152,14 +156,14 @@ impl Application for App {
             .set_int(gl, "gamma", if self.gamma_enabled { 1 } else { 0 });
 
         // floor
-        gl.bind_vertex_array(Some(self.plane_vao));
+        gl.bind_vertex_array(Some(self.plane_vao.raw()));
         gl.active_texture(TEXTURE0);
         gl.bind_texture(
             TEXTURE_2D,
             Some(if self.gamma_enabled {
-                self.floor_texture_gamma_corrected
+                self.floor_texture_gamma_corrected.raw()
             } else {
-                self.floor_texture
+                self.floor_texture.raw()
             }),
         );
         gl.draw_arrays(TRIANGLES, 0, 6);
@@ -172,7 +176,7 @@ impl Application for App {
     fn ui(
         &mut self,
         state: &crate::window::AppState,
-        _gl_ctx: &crate::window::GLContext,
+        gl_ctx: &crate::window::GLContext,
         egui_ctx: &egui::Context,
     ) {
         egui::Window::new("Info").show(egui_ctx, |ui| {
@@ -180,6 +184,7 @@ impl Application for App {
             ui.label("Press Space to enable gamma");
             ui.label(format!("Gamma Enabled: {}", self.gamma_enabled));
         });
+        crate::shader_reflection::shader_window(&gl_ctx.gl, egui_ctx);
     }
 
     unsafe fn process_input(&mut self, _ctx: &AppContext, input: &WinitInputHelper) {
@@ -188,53 +193,4 @@ impl Application for App {
 
         self.gamma_enabled = input.key_held(winit::keyboard::KeyCode::Space);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.shader.delete(gl);
-
-        gl.delete_vertex_array(self.plane_vao);
-        gl.delete_buffer(self.plane_vbo);
-    }
-}
-
-async fn load_texture(
-    gl: &Context,
-    file_name: &str,
-    gamma_correction: bool,
-) -> anyhow::Result<Texture> {
-    log::info!("Loading texture file_name: {}", file_name);
-    let bytes = load_binary(file_name).await?;
-    let img = image::load_from_memory(&bytes).expect("Failed to load texture from bytes");
-
-    let (width, height) = img.dimensions();
-    let data = img.into_rgba8();
-    let internal_format = if gamma_correction { SRGB8_ALPHA8 } else { RGBA };
-    let data_format = RGBA;
-
-    let texture = unsafe {
-        let texture = gl.create_texture().expect("Create texture");
-        gl.bind_texture(TEXTURE_2D, Some(texture));
-        gl.tex_image_2d(
-            TEXTURE_2D,
-            0,
-            internal_format as i32,
-            width as i32,
-            height as i32,
-            0,
-            data_format,
-            UNSIGNED_BYTE,
-            Some(&data),
-        );
-        gl.generate_mipmap(TEXTURE_2D);
-
-        gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_S, REPEAT as i32);
-        gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_T, REPEAT as i32);
-        gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MIN_FILTER, LINEAR_MIPMAP_LINEAR as i32);
-        gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MAG_FILTER, LINEAR as i32);
-
-        texture
-    };
-    Ok(texture)
 }
@@ -1,5 +1,8 @@
 use crate::camera::Camera;
+use crate::gl_object::{GlBuffer, GlVertexArray};
+use crate::render_target::{DepthAttachment, RenderTarget};
 use crate::shader::MyShader;
+use crate::shader_variants::ShaderVariants;
 use crate::window::{run, AppContext, Application, WindowInitInfo};
 use crate::{resources, texture};
 use glow::*;
@@ -84,23 +87,22 @@ const QUAD_VERTICES: [f32; 20] = [
 ];
 
 const LIGHT_POS: glm::Vec3 = glm::Vec3::new(-2.0, 4.0, -1.0);
-const SHADOW_WIDTH: i32 = 1024;
-const SHADOW_HEIGHT: i32 = 1024;
+const SHADOW_WIDTH: u32 = 1024;
+const SHADOW_HEIGHT: u32 = 1024;
 
 struct App {
-    plane_vao: VertexArray,
-    plane_vbo: Buffer,
+    plane_vao: GlVertexArray,
+    _plane_vbo: GlBuffer,
 
-    cube_vao: VertexArray,
-    cube_vbo: Buffer,
+    cube_vao: GlVertexArray,
+    _cube_vbo: GlBuffer,
 
-    quad_vao: VertexArray,
-    quad_vbo: Buffer,
+    quad_vao: GlVertexArray,
+    _quad_vbo: GlBuffer,
 
-    depth_map_fbo: Framebuffer,
-    depth_map: Texture,
+    depth_map_fbo: RenderTarget,
     wood_texture: texture::Texture,
-    shader: MyShader,
+    shader: ShaderVariants,
     simple_depth_shader: MyShader,
     debug_depth_quad_shader: MyShader,
     camera: Camera,
@@ -110,13 +112,14 @@ impl Application for App {
     async unsafe fn new(ctx: &AppContext) -> Self {
         let gl = ctx.gl();
 
-        let shader = MyShader::new_from_source(
-            gl,
-            include_str!("./shaders/_3_2_shadow_mapping.vs"),
-            include_str!("./shaders/_3_2_shadow_mapping.fs"),
-            Some(ctx.suggested_shader_version()),
-        )
-        .expect("Failed to create program");
+        // the shader of 5.3.3 without PCF
+        let mut shader = ShaderVariants::new(
+            MyShader::builder()
+                .version(Some(ctx.suggested_shader_version()))
+                .vertex(include_str!("./shaders/_3_2_shadow_mapping.vs"))
+                .fragment(include_str!("./shaders/_3_3_shadow_mapping.fs")),
+        );
+        let scene_shader = shader.variant(gl, &[]).expect("Failed to create program");
 
         let simple_depth_shader = MyShader::new_from_source(
             gl,
@@ -141,18 +144,17 @@ impl Application for App {
         gl.blend_func(SRC_ALPHA, ONE_MINUS_SRC_ALPHA);
 
         // PLANE
-        let plane_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(plane_vbo));
+        let plane_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(plane_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&PLANE_VERTICES),
             STATIC_DRAW,
         );
 
-        let plane_vao = gl
-            .create_vertex_array()
+        let plane_vao = GlVertexArray::new(gl)
             .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(plane_vao));
+        gl.bind_vertex_array(Some(plane_vao.raw()));
         let stride = 8 * size_of::<f32>() as i32;
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, stride, 0);
         gl.enable_vertex_attrib_array(0);
@@ -165,18 +167,17 @@ impl Application for App {
         gl.bind_vertex_array(None);
 
         // CUBE
-        let cube_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(cube_vbo));
+        let cube_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(cube_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&CUBE_VERTICES),
             STATIC_DRAW,
         );
 
-        let cube_vao = gl
-            .create_vertex_array()
+        let cube_vao = GlVertexArray::new(gl)
             .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(cube_vao));
+        gl.bind_vertex_array(Some(cube_vao.raw()));
         let stride = 8 * size_of::<f32>() as i32;
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, stride, 0);
         gl.enable_vertex_attrib_array(0);
@@ -189,18 +190,17 @@ impl Application for App {
         gl.bind_vertex_array(None);
 
         // QUAD
-        let quad_vbo = gl.create_buffer().expect("Cannot create vbo buffer");
-        gl.bind_buffer(ARRAY_BUFFER, Some(quad_vbo));
+        let quad_vbo = GlBuffer::new(gl).expect("Cannot create vbo buffer");
+        gl.bind_buffer(ARRAY_BUFFER, Some(quad_vbo.raw()));
         gl.buffer_data_u8_slice(
             ARRAY_BUFFER,
             bytemuck::cast_slice(&QUAD_VERTICES),
             STATIC_DRAW,
         );
 
-        let quad_vao = gl
-            .create_vertex_array()
+        let quad_vao = GlVertexArray::new(gl)
             .expect("Cannot create vertex array");
-        gl.bind_vertex_array(Some(quad_vao));
+        gl.bind_vertex_array(Some(quad_vao.raw()));
         let stride = 5 * size_of::<f32>() as i32;
         gl.vertex_attrib_pointer_f32(0, 3, FLOAT, false, stride, 0);
         gl.enable_vertex_attrib_array(0);
@@ -218,65 +218,30 @@ impl Application for App {
 
         // configure depth map FBO
         // -----------------------
-        let depth_map_fbo = gl.create_framebuffer().expect("Create framebuffer");
-        let depth_map = gl.create_texture().expect("Create texture");
-        gl.bind_texture(TEXTURE_2D, Some(depth_map));
-        // In WebGL2 DEPTH_COMPONENT is not a valid internal format. Use DEPTH_COMPONENT16, DEPTH_COMPONENT24 or DEPTH_COMPONENT32F
-        // https://webgl2fundamentals.org/webgl/lessons/webgl-render-to-texture.html
-        cfg_if::cfg_if! {
-            if #[cfg(target_arch = "wasm32")] {
-                let internal_format = DEPTH_COMPONENT24;
-            } else {
-                let internal_format = DEPTH_COMPONENT;
-            }
-        }
-        gl.tex_image_2d(
-            TEXTURE_2D,
-            0,
-            internal_format as i32,
-            SHADOW_WIDTH,
-            SHADOW_HEIGHT,
-            0,
-            DEPTH_COMPONENT,
-            UNSIGNED_INT, // WebGL2 throw error when I use FLOAT
-            None,
-        );
-
-        gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MIN_FILTER, NEAREST as i32);
-        gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MAG_FILTER, NEAREST as i32);
-        gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_S, REPEAT as i32);
-        gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_T, REPEAT as i32);
-        // attach depth texture as FBO's depth buffer
-        gl.bind_framebuffer(FRAMEBUFFER, Some(depth_map_fbo));
-        gl.framebuffer_texture_2d(
-            FRAMEBUFFER,
-            DEPTH_ATTACHMENT,
-            TEXTURE_2D,
-            Some(depth_map),
-            0,
-        );
-        gl.draw_buffer(NONE);
-        gl.read_buffer(NONE);
-        gl.bind_framebuffer(FRAMEBUFFER, None);
+        // with a depth texture as its only attachment
+        let depth_map_fbo = RenderTarget::builder(SHADOW_WIDTH, SHADOW_HEIGHT)
+            .depth(DepthAttachment::Texture)
+            .filter(NEAREST)
+            .build(gl)
+            .expect("Failed to create depth map framebuffer");
 
         // shader configuration
         // --------------------
-        shader.use_shader(gl);
-        shader.set_int(gl, "diffuseTexture", 0);
-        shader.set_int(gl, "shadowMap", 1);
+        scene_shader.use_shader(gl);
+        scene_shader.set_int(gl, "diffuseTexture", 0);
+        scene_shader.set_int(gl, "shadowMap", 1);
 
         debug_depth_quad_shader.use_shader(gl);
         debug_depth_quad_shader.set_int(gl, "depthMap", 0);
 
         Self {
             plane_vao,
-            plane_vbo,
+            _plane_vbo: plane_vbo,
             cube_vao,
-            cube_vbo,
+            _cube_vbo: cube_vbo,
             quad_vao,
-            quad_vbo,
+            _quad_vbo: quad_vbo,
             depth_map_fbo,
-            depth_map,
             wood_texture,
             shader,
             simple_depth_shader,
@@ -302,8 +267,7 @@ impl Application for App {
         self.simple_depth_shader
             .set_mat4(gl, "lightSpaceMatrix", &light_space_matrix);
 
-        gl.viewport(0, 0, SHADOW_WIDTH, SHADOW_HEIGHT);
-        gl.bind_framebuffer(FRAMEBUFFER, Some(self.depth_map_fbo));
+        self.depth_map_fbo.bind(gl);
         gl.clear(DEPTH_BUFFER_BIT);
         self.wood_texture.bind(gl, 0);
         self.render_scene(gl, &self.simple_depth_shader);
@@ -315,7 +279,8 @@ impl Application for App {
 
         // 2. render scene as normal using the generated depth/shadow map  
         // --------------------------------------------------------------
-        self.shader.use_shader(gl);
+        let shader = self.shader.get(&[]).unwrap();
+        shader.use_shader(gl);
         let projection = glm::perspective(
             ctx.width() as f32 / ctx.height() as f32,
             self.camera.zoom().to_radians(),
@@ -323,16 +288,16 @@ impl Application for App {
             100.0,
         );
         let view = self.camera.view_matrix();
-        self.shader.set_mat4(gl, "projection", &projection);
-        self.shader.set_mat4(gl, "view", &view);
+        shader.set_mat4(gl, "projection", &projection);
+        shader.set_mat4(gl, "view", &view);
         // set light uniforms
-        self.shader.set_vec3(gl, "viewPos", &self.camera.position());
-        self.shader.set_vec3(gl, "lightPos", &LIGHT_POS);
-        self.shader.set_mat4(gl, "lightSpaceMatrix", &light_space_matrix);
+        shader.set_vec3(gl, "viewPos", &self.camera.position());
+        shader.set_vec3(gl, "lightPos", &LIGHT_POS);
+        shader.set_mat4(gl, "lightSpaceMatrix", &light_space_matrix);
         self.wood_texture.bind(gl, 0);
         gl.active_texture(TEXTURE1);
-        gl.bind_texture(TEXTURE_2D, Some(self.depth_map));
-        self.render_scene(gl, &self.shader);
+        gl.bind_texture(TEXTURE_2D, self.depth_map_fbo.depth_texture());
+        self.render_scene(gl, shader);
 
         // render Depth map to quad for visual debugging
         // ---------------------------------------------
@@ -340,7 +305,7 @@ impl Application for App {
         self.debug_depth_quad_shader.try_set_float(gl, "near_plane", NEAR_PLANE);
         self.debug_depth_quad_shader.try_set_float(gl, "far_plane", FAR_PLANE);
         gl.active_texture(TEXTURE0);
-        gl.bind_texture(TEXTURE_2D, Some(self.depth_map));
+        gl.bind_texture(TEXTURE_2D, self.depth_map_fbo.depth_texture());
         // self.render_quad(gl);
     }
 
@@ -348,58 +313,37 @@ impl Application for App {
     fn ui(
         &mut self,
         state: &crate::window::AppState,
-        _gl_ctx: &crate::window::GLContext,
+        gl_ctx: &crate::window::GLContext,
         egui_ctx: &egui::Context,
     ) {
         egui::Window::new("Info").show(egui_ctx, |ui| {
             ui.label(format!("FPS: {:.1}", 1.0 / state.render_delta_time));
         });
+        crate::shader_reflection::shader_window(&gl_ctx.gl, egui_ctx);
     }
 
     unsafe fn process_input(&mut self, _ctx: &AppContext, input: &WinitInputHelper) {
         self.camera.process_keyboard_with_input(input);
         self.camera.process_mouse_with_input(input, true);
     }
-
-    unsafe fn exit(&mut self, ctx: &AppContext) {
-        let gl = ctx.gl();
-
-        self.wood_texture.delete(gl);
-        
-        self.shader.delete(gl);
-        self.simple_depth_shader.delete(gl);
-        self.debug_depth_quad_shader.delete(gl);
-
-        gl.delete_texture(self.depth_map);
-        gl.delete_framebuffer(self.depth_map_fbo);
-
-        gl.delete_vertex_array(self.plane_vao);
-        gl.delete_buffer(self.plane_vbo);
-
-        gl.delete_vertex_array(self.cube_vao);
-        gl.delete_buffer(self.cube_vbo);
-
-        gl.delete_vertex_array(self.quad_vao);
-        gl.delete_buffer(self.quad_vbo);
-    }
 }
 
 impl App {
     unsafe fn render_scene(&self, gl: &Context, shader: &MyShader) {
         // floor
         let model = glm::Mat4::identity();
-        shader.set_mat4(gl, "model", &model);
-        gl.bind_vertex_array(Some(self.plane_vao));
+        set_model(gl, shader, &model);
+        gl.bind_vertex_array(Some(self.plane_vao.raw()));
         gl.draw_arrays(TRIANGLES, 0, 6);
         // cubes
         let mut model = glm::translate(&glm::Mat4::identity(), &glm::vec3(0.0, 1.5, 0.0));
         model = glm::scale(&model, &glm::vec3(0.5, 0.5, 0.5));
-        shader.set_mat4(gl, "model", &model);
+        set_model(gl, shader, &model);
         self.render_cube(gl);
 
         model = glm::translate(&glm::Mat4::identity(), &glm::vec3(2.0, 0.0, 1.0));
         model = glm::scale(&model, &glm::vec3(0.5, 0.5, 0.5));
-        shader.set_mat4(gl, "model", &model);
+        set_model(gl, shader, &model);
         self.render_cube(gl);
 
         model = glm::translate(&glm::Mat4::identity(), &glm::vec3(-1.0, 0.0, 2.0));
@@ -409,14 +353,14 @@ impl App {
             &glm::vec3(1.0, 0.0, 1.0).normalize(),
         );
         model = glm::scale(&model, &glm::vec3(0.25, 0.25, 0.25));
-        shader.set_mat4(gl, "model", &model);
+        set_model(gl, shader, &model);
         self.render_cube(gl);
     }
 
     // render_cube() renders a 1x1 3D cube in NDC.
     // -------------------------------------------------
     unsafe fn render_cube(&self, gl: &Context) {
-        gl.bind_vertex_array(Some(self.cube_vao));
+        gl.bind_vertex_array(Some(self.cube_vao.raw()));
         gl.draw_arrays(TRIANGLES, 0, 36);
         gl.bind_vertex_array(None);
     }
@@ -425,8 +369,17 @@ impl App {
     // -----------------------------------------
     #[allow(dead_code)]
     unsafe fn render_quad(&self, gl: &Context) {
-        gl.bind_vertex_array(Some(self.quad_vao));
+        gl.bind_vertex_array(Some(self.quad_vao.raw()));
         gl.draw_arrays(TRIANGLE_STRIP, 0, 4);
         gl.bind_vertex_array(None);
     }
 }
+
+/// Sets `model` and, in the lighting pass, the normal matrix that goes with it,
+/// computed once per object instead of per vertex.
+fn set_model(gl: &Context, shader: &MyShader, model: &glm::Mat4) {
+    shader.set_mat4(gl, "model", model);
+    let normal_matrix = glm::transpose(&glm::inverse(&glm::mat4_to_mat3(model)));
+    // the depth pass doesn't need it
+    shader.try_set_mat3(gl, "normalMatrix", &normal_matrix);
+}
@@ -12,6 +12,8 @@ uniform sampler2D shadowMap;
 uniform vec3 lightPos;
 uniform vec3 viewPos;
 
+// compiled with PCF defined for biased, filtered shadows (5.3.3), without for the plain depth comparison (5.3.2)
+
 float ShadowCalculation(vec4 fragPosLightSpace)
 {
     // perform perspective divide
@@ -22,13 +24,13 @@ float ShadowCalculation(vec4 fragPosLightSpace)
     float closestDepth = texture(shadowMap, projCoords.xy).r;
     // get depth of current fragment from light's perspective
     float currentDepth = projCoords.z;
+#ifdef PCF
     // calculate bias (based on depth map resolution and slope)
     vec3 normal = normalize(Normal);
     vec3 lightDir = normalize(lightPos - FragPos);
     float bias = max(0.05 * (1.0 - dot(normal, lightDir)), 0.005);
-    //    // check whether current frag pos is in shadow
-    //    float shadow = currentDepth - bias > closestDepth ? 1.0 : 0.0;
 
+    // average the shadow of the surrounding texels to soften its edges
     float shadow = 0.0;
     vec2 size = vec2(textureSize(shadowMap, 0)); // webgl2 explict casting
     vec2 texelSize = 1.0 / size;
@@ -47,6 +49,10 @@ float ShadowCalculation(vec4 fragPosLightSpace)
     {
         shadow = 0.0;
     }
+#else
+    // check whether current frag pos is in shadow
+    float shadow = currentDepth > closestDepth ? 1.0 : 0.0;
+#endif
 
     return shadow;
 }
@@ -141,12 +141,7 @@ impl Camera {
             self.pitch += y_offset;
 
             if constrain_pitch {
-                if self.pitch > 89.0 {
-                    self.pitch = 89.0;
-                }
-                if self.pitch < -89.0 {
-                    self.pitch = -89.0;
-                }
+                self.pitch = self.pitch.clamp(-89.0, 89.0);
             }
 
             self.update_camera_vectors();
@@ -158,12 +153,7 @@ impl Camera {
         if self.zoom >= 1.0 && self.zoom <= 45.0 {
             self.zoom -= y_offset;
         }
-        if self.zoom <= 1.0 {
-            self.zoom = 1.0;
-        }
-        if self.zoom >= 45.0 {
-            self.zoom = 45.0;
-        }
+        self.zoom = self.zoom.clamp(1.0, 45.0);
     }
 
     fn update_camera_vectors(&mut self) {
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Source of time behind `AppState`, advanced once before every rendered frame.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+    fn advance(&mut self) {}
+}
+
+/// Wall-clock time, the default.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Simulated time moving forward by a fixed step per frame, whatever the real frame time is.
+/// Makes animated tutorials reproducible for tests, recordings and bug reports.
+/// `Application::update` runs once per step, a zero step freezes time and stops updates.
+#[derive(Debug, Copy, Clone)]
+pub struct FixedStepClock {
+    now: DateTime<Utc>,
+    step: chrono::Duration,
+}
+
+impl FixedStepClock {
+    pub fn new(step: Duration) -> Self {
+        Self {
+            now: DateTime::UNIX_EPOCH,
+            step: chrono::Duration::from_std(step).expect("Time step out of range"),
+        }
+    }
+}
+
+impl Clock for FixedStepClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    fn advance(&mut self) {
+        self.now += self.step;
+    }
+}
@@ -0,0 +1,103 @@
+use crate::gl_object::GlBuffer;
+use crate::window::SharedGl;
+use bytemuck::Pod;
+use glow::{Buffer, Context, HasContext, DYNAMIC_COPY, SHADER_STORAGE_BUFFER};
+use std::marker::PhantomData;
+
+/// A shader storage buffer of `T`s bound to the binding point a compute shader declares with
+/// `layout(std430, binding = N) buffer`. `T` has to match the std430 layout, e.g. pad a vec3
+/// to a vec4. The buffer can be used as a vertex buffer as well, see `buffer`.
+pub struct StorageBuffer<T: Pod> {
+    buffer: GlBuffer,
+    binding: u32,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod> StorageBuffer<T> {
+    pub fn new(gl: &SharedGl, binding: u32, data: &[T]) -> Self {
+        let buffer = unsafe {
+            let buffer = GlBuffer::new(gl).expect("Cannot create storage buffer");
+            gl.bind_buffer(SHADER_STORAGE_BUFFER, Some(buffer.raw()));
+            gl.buffer_data_u8_slice(
+                SHADER_STORAGE_BUFFER,
+                bytemuck::cast_slice(data),
+                DYNAMIC_COPY,
+            );
+            gl.bind_buffer(SHADER_STORAGE_BUFFER, None);
+            gl.bind_buffer_base(SHADER_STORAGE_BUFFER, binding, Some(buffer.raw()));
+            buffer
+        };
+        Self {
+            buffer,
+            binding,
+            len: data.len(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Replaces the contents, the buffer grows or shrinks to `data`.
+    pub fn set(&mut self, gl: &Context, data: &[T]) {
+        unsafe {
+            gl.bind_buffer(SHADER_STORAGE_BUFFER, Some(self.buffer.raw()));
+            if data.len() == self.len {
+                gl.buffer_sub_data_u8_slice(SHADER_STORAGE_BUFFER, 0, bytemuck::cast_slice(data));
+            } else {
+                gl.buffer_data_u8_slice(
+                    SHADER_STORAGE_BUFFER,
+                    bytemuck::cast_slice(data),
+                    DYNAMIC_COPY,
+                );
+                // a new data store has to be bound again
+                gl.bind_buffer_base(SHADER_STORAGE_BUFFER, self.binding, Some(self.buffer.raw()));
+            }
+            gl.bind_buffer(SHADER_STORAGE_BUFFER, None);
+        }
+        self.len = data.len();
+    }
+
+    /// Reads the contents back, waiting for the GPU. Put a
+    /// `memory_barrier(BUFFER_UPDATE_BARRIER_BIT)` between the dispatch and this.
+    pub fn read(&self, gl: &Context) -> Vec<T> {
+        let mut data = vec![T::zeroed(); self.len];
+        unsafe {
+            gl.bind_buffer(SHADER_STORAGE_BUFFER, Some(self.buffer.raw()));
+            gl.get_buffer_sub_data(
+                SHADER_STORAGE_BUFFER,
+                0,
+                bytemuck::cast_slice_mut(&mut data),
+            );
+            gl.bind_buffer(SHADER_STORAGE_BUFFER, None);
+        }
+        data
+    }
+
+    /// Binds the buffer to its binding point again, e.g. after another buffer took it.
+    pub fn bind(&self, gl: &Context) {
+        unsafe {
+            gl.bind_buffer_base(SHADER_STORAGE_BUFFER, self.binding, Some(self.buffer.raw()));
+        }
+    }
+
+    pub fn buffer(&self) -> Buffer {
+        self.buffer.raw()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Binds level 0 of `texture` to image `unit` for `image2D` uniforms declared with
+/// `layout(binding = unit, format) uniform`, `access` is `READ_ONLY`, `WRITE_ONLY` or `READ_WRITE`
+/// and `format` the sized internal format, e.g. `RGBA32F`.
+#[allow(dead_code)]
+pub fn bind_image(gl: &Context, unit: u32, texture: glow::Texture, access: u32, format: u32) {
+    unsafe {
+        gl.bind_image_texture(unit, texture, 0, false, 0, access, format);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::window::SharedGl;
+use glow::{Context, HasContext};
+use std::fmt;
+
+/// A kind of GL object `GlObject` can own. glow's handle types are aliases of
+/// `HasContext` associated types, so the kinds are marker types instead of the handles.
+pub trait GlResource {
+    type Raw: Copy + fmt::Debug;
+
+    /// # Safety
+    /// Needs the context to be current.
+    unsafe fn create(gl: &Context) -> Result<Self::Raw, String>;
+    /// # Safety
+    /// Needs the context to be current.
+    unsafe fn delete(gl: &Context, raw: Self::Raw);
+}
+
+macro_rules! gl_resources {
+    ($($kind:ident($raw:ident): $create:ident, $delete:ident;)*) => {
+        $(
+            #[derive(Debug)]
+            pub enum $kind {}
+
+            impl GlResource for $kind {
+                type Raw = glow::$raw;
+
+                unsafe fn create(gl: &Context) -> Result<Self::Raw, String> {
+                    gl.$create()
+                }
+
+                unsafe fn delete(gl: &Context, raw: Self::Raw) {
+                    gl.$delete(raw)
+                }
+            }
+        )*
+    };
+}
+
+gl_resources! {
+    BufferKind(Buffer): create_buffer, delete_buffer;
+    VertexArrayKind(VertexArray): create_vertex_array, delete_vertex_array;
+    TextureKind(Texture): create_texture, delete_texture;
+    FramebufferKind(Framebuffer): create_framebuffer, delete_framebuffer;
+    RenderbufferKind(Renderbuffer): create_renderbuffer, delete_renderbuffer;
+    ProgramKind(Program): create_program, delete_program;
+}
+
+/// A GL object that is deleted when dropped, so there is nothing to clean up in `Application::exit`.
+/// It keeps the context alive and isn't `Clone`, share it with `Rc` to use it in several places.
+pub struct GlObject<T: GlResource> {
+    gl: SharedGl,
+    raw: T::Raw,
+}
+
+pub type GlBuffer = GlObject<BufferKind>;
+pub type GlVertexArray = GlObject<VertexArrayKind>;
+pub type GlTexture = GlObject<TextureKind>;
+pub type GlFramebuffer = GlObject<FramebufferKind>;
+pub type GlRenderbuffer = GlObject<RenderbufferKind>;
+pub type GlProgram = GlObject<ProgramKind>;
+
+#[allow(dead_code)]
+impl<T: GlResource> GlObject<T> {
+    pub fn new(gl: &SharedGl) -> Result<Self, String> {
+        let raw = unsafe { T::create(gl)? };
+        Ok(Self::from_raw(gl, raw))
+    }
+
+    /// Takes over `raw`, e.g. from `gl.create_*`, to delete it on drop.
+    pub fn from_raw(gl: &SharedGl, raw: T::Raw) -> Self {
+        Self {
+            gl: gl.clone(),
+            raw,
+        }
+    }
+
+    pub fn raw(&self) -> T::Raw {
+        self.raw
+    }
+
+    /// The context the object belongs to.
+    pub fn gl(&self) -> &SharedGl {
+        &self.gl
+    }
+}
+
+impl<T: GlResource> Drop for GlObject<T> {
+    fn drop(&mut self) {
+        unsafe { T::delete(&self.gl, self.raw) }
+    }
+}
+
+impl<T: GlResource> fmt::Debug for GlObject<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GlObject").field(&self.raw).finish()
+    }
+}
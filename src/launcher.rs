@@ -0,0 +1,178 @@
+use crate::registry::{Chapter, Platform, Tutorial, TUTORIALS};
+use crate::window::{
+    capture_app, run, AnyApplication, AppContext, AppState, Application, GLContext, WindowInitInfo,
+};
+use glow::HasContext;
+use std::panic::AssertUnwindSafe;
+use winit_input_helper::WinitInputHelper;
+
+/// Opens one window listing every tutorial and hosts the picked one, switching without restarting.
+#[allow(clippy::missing_safety_doc)]
+pub async unsafe fn run_launcher() {
+    let init_info = WindowInitInfo::builder()
+        .title("LearnOpenGL Rust".to_string())
+        .build();
+    unsafe {
+        run::<Launcher>(init_info).await;
+    }
+}
+
+struct Hosted {
+    tutorial: &'static Tutorial,
+    app: Box<dyn AnyApplication>,
+}
+
+struct Launcher {
+    current: Option<Hosted>,
+    /// picked in the ui, switched to on the next render where the context is at hand
+    pending: Option<&'static Tutorial>,
+}
+
+impl Launcher {
+    unsafe fn switch_to(&mut self, tutorial: &'static Tutorial, ctx: &AppContext) {
+        if let Some(mut hosted) = self.current.take() {
+            log::info!("Leaving tutorial {}", hosted.tutorial.id);
+            hosted.app.exit(ctx);
+        }
+        reset_gl_state(ctx.gl());
+
+        let Some((init_info, new_app)) = capture_app(tutorial.run()) else {
+            log::error!("Tutorial {} doesn't go through window::run", tutorial.id);
+            return;
+        };
+        if init_info.num_samples > 1 {
+            log::warn!(
+                "Tutorial {} asks for {} samples, the launcher window may have fewer",
+                tutorial.id,
+                init_info.num_samples
+            );
+        }
+        log::info!("Running tutorial {} {}", tutorial.id, tutorial.title);
+        // a tutorial failing to load, e.g. a missing model, shouldn't take the launcher down
+        match std::panic::catch_unwind(AssertUnwindSafe(|| new_app(ctx))) {
+            Ok(mut app) => {
+                app.resize(ctx, ctx.width(), ctx.height());
+                self.current = Some(Hosted { tutorial, app });
+            }
+            Err(_) => {
+                log::error!("Tutorial {} failed to start", tutorial.id);
+                reset_gl_state(ctx.gl());
+            }
+        }
+    }
+}
+
+impl Application for Launcher {
+    async unsafe fn new(_ctx: &AppContext) -> Self {
+        Self {
+            current: None,
+            pending: None,
+        }
+    }
+
+    fn ui(&mut self, state: &AppState, gl_ctx: &GLContext, egui_ctx: &egui::Context) {
+        let current_id = self.current.as_ref().map(|hosted| hosted.tutorial.id);
+        egui::Window::new("Tutorials")
+            .default_pos([10.0, 10.0])
+            .vscroll(true)
+            .show(egui_ctx, |ui| {
+                for chapter in Chapter::ALL {
+                    egui::CollapsingHeader::new(chapter.title())
+                        .default_open(chapter == Chapter::GettingStarted)
+                        .show(ui, |ui| {
+                            for tutorial in TUTORIALS.iter().filter(|t| t.chapter == chapter) {
+                                let reason = if tutorial.own_window {
+                                    Some("opens its own window".to_string())
+                                } else {
+                                    tutorial.unsupported_reason(Platform::Desktop)
+                                };
+                                let label = egui::SelectableLabel::new(
+                                    current_id == Some(tutorial.id),
+                                    format!("{} {}", tutorial.id, tutorial.title),
+                                );
+                                let response = ui.add_enabled(reason.is_none(), label);
+                                if let Some(reason) = reason {
+                                    response.on_disabled_hover_text(reason);
+                                } else if response.clicked() {
+                                    self.pending = Some(tutorial);
+                                }
+                            }
+                        });
+                }
+            });
+
+        if let Some(hosted) = self.current.as_mut() {
+            hosted.app.ui(state, gl_ctx, egui_ctx);
+        }
+    }
+
+    unsafe fn render(&mut self, ctx: &AppContext) {
+        if let Some(tutorial) = self.pending.take() {
+            self.switch_to(tutorial, ctx);
+        }
+        match self.current.as_mut() {
+            Some(hosted) => hosted.app.render(ctx),
+            None => {
+                let gl = ctx.gl();
+                gl.clear_color(0.1, 0.1, 0.1, 1.0);
+                gl.clear(glow::COLOR_BUFFER_BIT);
+            }
+        }
+    }
+
+    unsafe fn update(&mut self, update_delta_time: f32) {
+        if let Some(hosted) = self.current.as_mut() {
+            hosted.app.update(update_delta_time);
+        }
+    }
+
+    unsafe fn resize(&mut self, ctx: &AppContext, width: u32, height: u32) {
+        match self.current.as_mut() {
+            Some(hosted) => hosted.app.resize(ctx, width, height),
+            None => ctx.gl().viewport(0, 0, width as i32, height as i32),
+        }
+    }
+
+    unsafe fn process_input(&mut self, ctx: &AppContext, input: &WinitInputHelper) {
+        if let Some(hosted) = self.current.as_mut() {
+            hosted.app.process_input(ctx, input);
+        }
+    }
+
+    unsafe fn exit(&mut self, ctx: &AppContext) {
+        if let Some(mut hosted) = self.current.take() {
+            hosted.app.exit(ctx);
+        }
+    }
+}
+
+/// Puts back the GL defaults the tutorials rely on, whatever the previous one left behind.
+unsafe fn reset_gl_state(gl: &glow::Context) {
+    for cap in [
+        glow::BLEND,
+        glow::CULL_FACE,
+        glow::DEPTH_TEST,
+        glow::POLYGON_OFFSET_FILL,
+        glow::PROGRAM_POINT_SIZE,
+        glow::SAMPLE_ALPHA_TO_COVERAGE,
+        glow::SAMPLE_COVERAGE,
+        glow::SCISSOR_TEST,
+        glow::STENCIL_TEST,
+        glow::FRAMEBUFFER_SRGB,
+    ] {
+        gl.disable(cap);
+    }
+    gl.enable(glow::MULTISAMPLE);
+    gl.depth_func(glow::LESS);
+    gl.depth_mask(true);
+    gl.stencil_func(glow::ALWAYS, 0, 0xFF);
+    gl.stencil_op(glow::KEEP, glow::KEEP, glow::KEEP);
+    gl.stencil_mask(0xFF);
+    gl.blend_func(glow::ONE, glow::ZERO);
+    gl.cull_face(glow::BACK);
+    gl.front_face(glow::CCW);
+    gl.polygon_mode(glow::FRONT_AND_BACK, glow::FILL);
+    gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+    gl.bind_vertex_array(None);
+    gl.use_program(None);
+}
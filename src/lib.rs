@@ -1,9 +1,22 @@
 mod camera;
+mod clock;
+mod compute;
+mod gl_object;
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+mod launcher;
 mod mesh;
 mod model;
+mod post_process;
+#[cfg(not(target_arch = "wasm32"))]
+mod program_cache;
+mod registry;
+mod render_target;
 mod resources;
 mod shader;
+mod shader_reflection;
+mod shader_variants;
 mod texture;
+mod uniform_buffer;
 mod window;
 
 mod _1_getting_started;
@@ -12,11 +25,26 @@ mod _3_model_loading;
 mod _4_advanced_opengl;
 mod _5_advanced_lighting;
 
-use _1_getting_started::*;
-use _2_lighting::*;
-use _3_model_loading::*;
-use _4_advanced_opengl::*;
-use _5_advanced_lighting::*;
+pub use clock::{Clock, FixedStepClock, SystemClock};
+pub use compute::StorageBuffer;
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+pub use launcher::run_launcher;
+pub use post_process::{CustomEffect, Effect, Kernel, PostProcessStack};
+pub use registry::{find_tutorial, Chapter, GlFeature, Platform, Tutorial, TUTORIALS};
+pub use render_target::{
+    ColorFormat, DepthAttachment, RenderTarget, RenderTargetBuilder, RenderTargetError,
+};
+pub use shader::{MyShader, PreprocessedSource, ShaderType};
+pub use shader_reflection::{ActiveAttribute, ActiveBlock, ActiveUniform, ShaderReflection};
+pub use texture::{
+    CubemapTexture, Texture, TextureChannels, TextureFormat, TextureOptions, TextureType,
+};
+pub use uniform_buffer::{std140_bytes, Std140, UniformBuffer};
+#[cfg(not(target_arch = "wasm32"))]
+pub use window::headless_available;
+#[cfg(not(any(target_arch = "wasm32", target_os = "macos")))]
+pub use window::HeadlessContext;
+pub use window::{set_launch_options, LaunchOptions, SharedGl};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -24,84 +52,44 @@ use wasm_bindgen::prelude::*;
 #[allow(clippy::missing_safety_doc)]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 pub async unsafe fn run_tutorial(tutorial_id: String) {
-    log::info!("Running tutorial {}", tutorial_id);
-    match tutorial_id.as_str() {
-        "1_1_1" => main_1_1_1().await,
-        "1_1_2" => main_1_1_2().await,
-        "1_2_1" => main_1_2_1().await,
-        "1_2_2" => main_1_2_2().await,
-        "1_2_3" => main_1_2_3().await,
-        "1_2_4" => main_1_2_4().await,
-        "1_2_5" => main_1_2_5().await,
-        "1_3_1" => main_1_3_1().await,
-        "1_3_2" => main_1_3_2().await,
-        "1_3_3" => main_1_3_3().await,
-        "1_3_4" => main_1_3_4().await,
-        "1_3_5" => main_1_3_5().await,
-        "1_3_6" => main_1_3_6().await,
-        "1_4_1" => main_1_4_1().await,
-        "1_4_2" => main_1_4_2().await,
-        "1_4_3" => main_1_4_3().await,
-        "1_4_4" => main_1_4_4().await,
-        "1_4_5" => main_1_4_5().await,
-        "1_4_6" => main_1_4_6().await,
-        "1_5_1" => main_1_5_1().await,
-        "1_5_2" => main_1_5_2().await,
-        "1_5_3" => main_1_5_3().await,
-        "1_6_1" => main_1_6_1().await,
-        "1_6_2" => main_1_6_2().await,
-        "1_6_3" => main_1_6_3().await,
-        "1_6_4" => main_1_6_4().await,
-        "1_7_1" => main_1_7_1().await,
-        "1_7_2" => main_1_7_2().await,
-        "1_7_3" => main_1_7_3().await,
-        "1_7_4" => main_1_7_4().await,
-        "1_7_5" => main_1_7_5().await,
-        "1_7_6" => main_1_7_6().await,
-        "2_1_1" => main_2_1_1().await,
-        "2_2_1" => main_2_2_1().await,
-        "2_2_2" => main_2_2_2().await,
-        "2_2_3" => main_2_2_3().await,
-        "2_2_4" => main_2_2_4().await,
-        "2_2_5" => main_2_2_5().await,
-        "2_3_1" => main_2_3_1().await,
-        "2_3_2" => main_2_3_2().await,
-        "2_4_1" => main_2_4_1().await,
-        "2_4_2" => main_2_4_2().await,
-        "2_4_3" => main_2_4_3().await,
-        "2_4_4" => main_2_4_4().await,
-        "2_4_5" => main_2_4_5().await,
-        "2_5_1" => main_2_5_1().await,
-        "2_5_2" => main_2_5_2().await,
-        "2_5_3" => main_2_5_3().await,
-        "2_5_4" => main_2_5_4().await,
-        "2_6_1" => main_2_6_1().await,
-        "2_6_2" => main_2_6_2().await,
-        "3_1_1" => main_3_1_1().await,
-        "4_1_1" => main_4_1_1().await,
-        "4_1_2" => main_4_1_2().await,
-        "4_2_1" => main_4_2_1().await,
-        "4_3_1" => main_4_3_1().await,
-        "4_3_2" => main_4_3_2().await,
-        "4_4_1" => main_4_4_1().await,
-        "4_5_1" => main_4_5_1().await,
-        "4_5_2" => main_4_5_2().await,
-        "4_5_3" => main_4_5_3().await,
-        "4_6_1" => main_4_6_1().await,
-        "4_6_2" => main_4_6_2().await,
-        "4_8_1" => main_4_8_1().await,
-        "4_9_1" => main_4_9_1().await,
-        "4_9_2" => main_4_9_2().await,
-        "4_9_3" => main_4_9_3().await,
-        "4_10_1" => main_4_10_1().await,
-        "4_10_2" => main_4_10_2().await,
-        "4_10_3" => main_4_10_3().await,
-        "4_11_1" => main_4_11_1().await,
-        "5_1_1" => main_5_1_1().await,
-        "5_2_1" => main_5_2_1().await,
-        "5_3_1" => main_5_3_1().await,
-        "5_3_2" => main_5_3_2().await,
-        "5_3_3" => main_5_3_3().await,
-        _ => log::error!("Unknown tutorial id: {}", tutorial_id),
+    let Some(tutorial) = find_tutorial(&tutorial_id) else {
+        log::error!("Unknown tutorial id: {}", tutorial_id);
+        return;
+    };
+    if let Some(reason) = tutorial.unsupported_reason(Platform::current()) {
+        log::error!(
+            "Skipping tutorial {} {}: it {}",
+            tutorial.id,
+            tutorial.title,
+            reason
+        );
+        return;
     }
+    log::info!("Running tutorial {} {}", tutorial.id, tutorial.title);
+    tutorial.run().await
+}
+
+/// Tutorial metadata for the web page to list.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(getter_with_clone)]
+pub struct TutorialInfo {
+    pub id: String,
+    pub chapter: String,
+    pub title: String,
+    /// why it can't run in this browser, if it can't
+    pub unsupported: Option<String>,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn list_tutorials() -> Vec<TutorialInfo> {
+    TUTORIALS
+        .iter()
+        .map(|tutorial| TutorialInfo {
+            id: tutorial.id.to_string(),
+            chapter: tutorial.chapter.to_string(),
+            title: tutorial.title.to_string(),
+            unsupported: tutorial.unsupported_reason(Platform::Web),
+        })
+        .collect()
 }
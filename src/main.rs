@@ -1,19 +1,107 @@
-use lib_learn_opengl_rs::run_tutorial;
+use lib_learn_opengl_rs::{run_tutorial, set_launch_options, LaunchOptions, Platform, TUTORIALS};
 use std::env;
+use std::time::Duration;
+
+const USAGE: &str =
+    "Call with the number of the tutorial, e.g. `1_1_2` for _1_2_hello_window_clear.rs,
+or without one to browse all tutorials in one window (needs the egui-support feature)
+
+Options:
+  --list               list all tutorials
+  --headless           render offscreen without a window, e.g. on CI
+  --frames <N>         number of frames to render before exiting
+  --screenshot <FILE>  save the last frame as PNG, e.g. to regenerate misc/screenshots
+  --time-step <SECS>   advance time by a fixed step per frame instead of the wall clock
+  --width <PIXELS>     window width
+  --height <PIXELS>    window height
+  --title <TITLE>      window title
+  --major <N>          GL context major version
+  --minor <N>          GL context minor version
+  --num-samples <N>    MSAA samples of the default framebuffer
+  --vsync <on|off>     wait for vblank on swap, off also lifts the 60 FPS cap";
 
 fn main() {
     env_logger::init();
 
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() != 2 {
-        println!(
-            "Call with the number of the tutorial, e.g. `1_1_2` for _1_2_hello_window_clear.rs"
-        );
-        std::process::exit(1);
+    let mut tutorial_id = None;
+    let mut options = LaunchOptions::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--list" => {
+                list_tutorials();
+                return;
+            }
+            "--headless" => options.headless = true,
+            "--frames" => options.frames = Some(parse_value(&mut args, &arg)),
+            "--screenshot" => options.screenshot = Some(parse_value(&mut args, &arg)),
+            "--time-step" => {
+                options.time_step = Some(Duration::from_secs_f64(parse_value(&mut args, &arg)))
+            }
+            "--width" => options.width = Some(parse_value(&mut args, &arg)),
+            "--height" => options.height = Some(parse_value(&mut args, &arg)),
+            "--title" => options.title = Some(parse_value(&mut args, &arg)),
+            "--major" => options.major = Some(parse_value(&mut args, &arg)),
+            "--minor" => options.minor = Some(parse_value(&mut args, &arg)),
+            "--num-samples" => options.num_samples = Some(parse_value(&mut args, &arg)),
+            "--vsync" => {
+                options.vsync = match parse_value::<String>(&mut args, &arg).as_str() {
+                    "on" => Some(true),
+                    "off" => Some(false),
+                    value => {
+                        println!("Invalid value for {}: {}", arg, value);
+                        exit_with_usage();
+                    }
+                }
+            }
+            _ if tutorial_id.is_none() && !arg.starts_with("--") => tutorial_id = Some(arg),
+            _ => exit_with_usage(),
+        }
+    }
+    #[cfg(feature = "egui-support")]
+    if tutorial_id.is_none() && !options.headless {
+        set_launch_options(options);
+        unsafe {
+            pollster::block_on(lib_learn_opengl_rs::run_launcher());
+        }
+        return;
     }
+    let Some(tutorial_id) = tutorial_id else {
+        exit_with_usage();
+    };
 
-    let tutorial_id = &args[1];
+    set_launch_options(options);
     unsafe {
-        pollster::block_on(run_tutorial(tutorial_id.to_string()));
+        pollster::block_on(run_tutorial(tutorial_id));
+    }
+}
+
+fn list_tutorials() {
+    let mut chapter = None;
+    for tutorial in &TUTORIALS {
+        if chapter != Some(tutorial.chapter) {
+            chapter = Some(tutorial.chapter);
+            println!("{}", tutorial.chapter);
+        }
+        match tutorial.unsupported_reason(Platform::Desktop) {
+            Some(reason) => println!("  {:<8}{} ({})", tutorial.id, tutorial.title, reason),
+            None => println!("  {:<8}{}", tutorial.id, tutorial.title),
+        }
     }
 }
+
+fn parse_value<T: std::str::FromStr>(args: &mut impl Iterator<Item = String>, name: &str) -> T {
+    let Some(value) = args.next() else {
+        println!("Missing value for {}", name);
+        exit_with_usage();
+    };
+    value.parse().unwrap_or_else(|_| {
+        println!("Invalid value for {}: {}", name, value);
+        exit_with_usage();
+    })
+}
+
+fn exit_with_usage() -> ! {
+    println!("{}", USAGE);
+    std::process::exit(1);
+}
@@ -1,7 +1,9 @@
+use crate::gl_object::{GlBuffer, GlVertexArray};
 use crate::shader::MyShader;
 use crate::texture::{map_texture_type_to_string, Texture, TextureType};
+use crate::window::SharedGl;
 use bytemuck::{offset_of, Pod, Zeroable};
-use glow::{Buffer, Context, HasContext, VertexArray};
+use glow::{Context, HasContext};
 use nalgebra_glm as glm;
 
 #[repr(C)]
@@ -14,35 +16,36 @@ pub struct Vertex {
 
 #[derive(Debug)]
 pub struct Material {
+    /// from the `.mtl` file, for debugging
+    #[allow(dead_code)]
     pub name: String,
     pub textures: Vec<Texture>,
 }
 
 #[derive(Debug)]
 pub struct Mesh {
+    /// of the object in the `.obj` file, for debugging
+    #[allow(dead_code)]
     pub name: String,
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
     pub material_id: usize,
-    pub vao: VertexArray,
-    pub vbo: Buffer,
-    pub ebo: Buffer,
+    pub vao: GlVertexArray,
+    pub vbo: GlBuffer,
+    pub ebo: GlBuffer,
 }
 
 impl Mesh {
     pub fn new(
-        gl: &Context,
+        gl: &SharedGl,
         name: &str,
         vertices: Vec<Vertex>,
         indices: Vec<u32>,
         material_id: usize,
     ) -> Self {
-        let vao = unsafe {
-            gl.create_vertex_array()
-                .expect("Cannot create vertex array")
-        };
-        let vbo = unsafe { gl.create_buffer().expect("Cannot create buffer") };
-        let ebo = unsafe { gl.create_buffer().expect("Cannot create buffer") };
+        let vao = GlVertexArray::new(gl).expect("Cannot create vertex array");
+        let vbo = GlBuffer::new(gl).expect("Cannot create buffer");
+        let ebo = GlBuffer::new(gl).expect("Cannot create buffer");
         let mut mesh = Mesh {
             name: name.to_string(),
             vertices,
@@ -58,16 +61,16 @@ impl Mesh {
 
     fn setup_mesh(&mut self, gl: &Context) {
         unsafe {
-            gl.bind_vertex_array(Some(self.vao));
+            gl.bind_vertex_array(Some(self.vao.raw()));
 
-            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo.raw()));
             gl.buffer_data_u8_slice(
                 glow::ARRAY_BUFFER,
                 bytemuck::cast_slice(&self.vertices),
                 glow::STATIC_DRAW,
             );
 
-            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo));
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo.raw()));
             gl.buffer_data_u8_slice(
                 glow::ELEMENT_ARRAY_BUFFER,
                 bytemuck::cast_slice(&self.indices),
@@ -148,7 +151,7 @@ impl Mesh {
                 gl.bind_texture(glow::TEXTURE_2D, Some(texture.raw()));
             }
 
-            gl.bind_vertex_array(Some(self.vao));
+            gl.bind_vertex_array(Some(self.vao.raw()));
             gl.draw_elements(
                 glow::TRIANGLES,
                 self.indices.len() as i32,
@@ -160,12 +163,4 @@ impl Mesh {
             gl.active_texture(glow::TEXTURE0);
         }
     }
-
-    pub fn delete(&self, gl: &Context) {
-        unsafe {
-            gl.delete_vertex_array(self.vao);
-            gl.delete_buffer(self.vbo);
-            gl.delete_buffer(self.ebo);
-        }
-    }
 }
@@ -2,6 +2,7 @@ use crate::mesh::{Material, Mesh};
 use crate::shader::MyShader;
 use glow::Context;
 
+/// Meshes and material textures are deleted when the model is dropped.
 #[derive(Debug)]
 pub struct Model {
     pub meshes: Vec<Mesh>,
@@ -14,10 +15,4 @@ impl Model {
             mesh.draw(gl, &self.materials, shader);
         }
     }
-
-    pub fn delete(&self, gl: &Context) {
-        for mesh in &self.meshes {
-            mesh.delete(gl);
-        }
-    }
 }
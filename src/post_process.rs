@@ -0,0 +1,262 @@
+use crate::gl_object::{GlBuffer, GlVertexArray};
+use crate::render_target::{ColorFormat, RenderTarget, RenderTargetError};
+use crate::shader::MyShader;
+use crate::shader_variants::ShaderVariants;
+use crate::window::SharedGl;
+use glow::*;
+use std::fmt;
+use std::mem::size_of;
+
+#[rustfmt::skip]
+const QUAD_VERTICES: [f32; 24] = [
+    // positions    texture Coords
+    -1.0,  1.0,  0.0, 1.0,
+    -1.0, -1.0,  0.0, 0.0,
+    1.0, -1.0,  1.0, 0.0,
+
+    -1.0,  1.0,  0.0, 1.0,
+    1.0, -1.0,  1.0, 0.0,
+    1.0,  1.0,  1.0, 1.0
+];
+
+/// A 3x3 convolution.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Kernel {
+    /// row by row from the top left
+    pub weights: [f32; 9],
+    /// distance between the samples in texture coordinates
+    pub offset: f32,
+}
+
+impl Kernel {
+    #[rustfmt::skip]
+    pub const SHARPEN: Self = Self::new([
+        -1.0, -1.0, -1.0,
+        -1.0,  9.0, -1.0,
+        -1.0, -1.0, -1.0,
+    ]);
+    #[rustfmt::skip]
+    pub const BLUR: Self = Self::new([
+        1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0,
+        2.0 / 16.0, 4.0 / 16.0, 2.0 / 16.0,
+        1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0,
+    ]);
+    #[rustfmt::skip]
+    pub const EDGE_DETECTION: Self = Self::new([
+        1.0,  1.0, 1.0,
+        1.0, -8.0, 1.0,
+        1.0,  1.0, 1.0,
+    ]);
+
+    /// Samples `1.0 / 300.0` apart.
+    pub const fn new(weights: [f32; 9]) -> Self {
+        Self {
+            weights,
+            offset: 1.0 / 300.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Effect {
+    Inversion,
+    Grayscale,
+    Kernel(Kernel),
+    /// a shader of the application's, see `PostProcessStack::add_custom`
+    Custom(CustomEffect),
+}
+
+/// Identifies a shader added with `PostProcessStack::add_custom`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CustomEffect(usize);
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Effect::Inversion => write!(f, "Inversion"),
+            Effect::Grayscale => write!(f, "Grayscale"),
+            Effect::Kernel(kernel) if *kernel == Kernel::SHARPEN => write!(f, "Sharpen"),
+            Effect::Kernel(kernel) if *kernel == Kernel::BLUR => write!(f, "Blur"),
+            Effect::Kernel(kernel) if *kernel == Kernel::EDGE_DETECTION => {
+                write!(f, "Edge Detection")
+            }
+            Effect::Kernel(_) => write!(f, "Kernel"),
+            Effect::Custom(CustomEffect(index)) => write!(f, "Custom {}", index),
+        }
+    }
+}
+
+impl Effect {
+    /// defines of the effect's variant of `shaders/post_process.fs`, `None` for custom effects
+    fn defines(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Effect::Inversion => Some(&["INVERSION"]),
+            Effect::Grayscale => Some(&["GRAYSCALE"]),
+            Effect::Kernel(_) => Some(&["KERNEL"]),
+            Effect::Custom(_) => None,
+        }
+    }
+}
+
+/// Draws a texture, e.g. the color attachment the scene was rendered to, through `effects` in order,
+/// each one reading what the one before wrote. Two render targets of the window's size take turns
+/// as the input and the output, the last effect draws to the given target or the window.
+pub struct PostProcessStack {
+    pub effects: Vec<Effect>,
+    shader: ShaderVariants,
+    custom: Vec<MyShader>,
+    targets: [RenderTarget; 2],
+    quad_vao: GlVertexArray,
+    _quad_vbo: GlBuffer,
+}
+
+impl PostProcessStack {
+    /// Vertex shader of every pass, passes `TexCoords` of the screen quad on to the fragment shader,
+    /// use it for the shaders of `add_custom`.
+    pub const VERTEX_SHADER: &'static str = include_str!("shaders/post_process.vs");
+
+    /// `format` is the one of the intermediate targets.
+    pub fn new(
+        gl: &SharedGl,
+        width: u32,
+        height: u32,
+        format: ColorFormat,
+        shader_version: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let mut shader = ShaderVariants::new(
+            MyShader::builder()
+                .version(shader_version)
+                .vertex(Self::VERTEX_SHADER)
+                .fragment(include_str!("shaders/post_process.fs")),
+        );
+        for defines in [&[][..], &["INVERSION"], &["GRAYSCALE"], &["KERNEL"]] {
+            let variant = shader.variant(gl, defines)?;
+            variant.use_shader(gl);
+            variant.set_int(gl, "screenTexture", 0);
+        }
+
+        let target = || RenderTarget::builder(width, height).color(format).build(gl);
+        let targets = [target()?, target()?];
+
+        let (quad_vao, quad_vbo) = unsafe {
+            let quad_vbo = GlBuffer::new(gl).map_err(anyhow::Error::msg)?;
+            gl.bind_buffer(ARRAY_BUFFER, Some(quad_vbo.raw()));
+            gl.buffer_data_u8_slice(
+                ARRAY_BUFFER,
+                bytemuck::cast_slice(&QUAD_VERTICES),
+                STATIC_DRAW,
+            );
+
+            let quad_vao = GlVertexArray::new(gl).map_err(anyhow::Error::msg)?;
+            gl.bind_vertex_array(Some(quad_vao.raw()));
+            let stride = 4 * size_of::<f32>() as i32;
+            gl.vertex_attrib_pointer_f32(0, 2, FLOAT, false, stride, 0);
+            gl.enable_vertex_attrib_array(0);
+            gl.vertex_attrib_pointer_f32(1, 2, FLOAT, false, stride, 2 * size_of::<f32>() as i32);
+            gl.enable_vertex_attrib_array(1);
+            gl.bind_vertex_array(None);
+            gl.bind_buffer(ARRAY_BUFFER, None);
+            (quad_vao, quad_vbo)
+        };
+
+        Ok(Self {
+            effects: Vec::new(),
+            shader,
+            custom: Vec::new(),
+            targets,
+            quad_vao,
+            _quad_vbo: quad_vbo,
+        })
+    }
+
+    /// Keeps the intermediate targets at the window size, call it from `Application::resize`.
+    pub fn resize(
+        &mut self,
+        gl: &SharedGl,
+        width: u32,
+        height: u32,
+    ) -> Result<(), RenderTargetError> {
+        for target in &mut self.targets {
+            target.resize(gl, width, height)?;
+        }
+        Ok(())
+    }
+
+    /// Adds an effect drawn with `shader`, which samples `screenTexture` at `TexCoords`, e.g. with
+    /// `VERTEX_SHADER` as its vertex stage. Its own uniforms are set through `custom_shader`.
+    pub fn add_custom(&mut self, gl: &Context, shader: MyShader) -> CustomEffect {
+        shader.use_shader(gl);
+        shader.try_set_int(gl, "screenTexture", 0);
+        self.custom.push(shader);
+        CustomEffect(self.custom.len() - 1)
+    }
+
+    pub fn custom_shader(&self, effect: CustomEffect) -> &MyShader {
+        &self.custom[effect.0]
+    }
+
+    /// Picks up edits of custom shaders loaded from files without restarting,
+    /// see `MyShader::reload_if_changed`. The built-in effects are compiled in.
+    pub fn reload_if_changed(&mut self, gl: &SharedGl) -> bool {
+        let mut changed = false;
+        for (index, shader) in self.custom.iter_mut().enumerate() {
+            if shader.reload_if_changed(gl) {
+                shader.use_shader(gl);
+                shader.try_set_int(gl, "screenTexture", 0);
+                log::info!("Reloaded custom effect {}", index);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Draws `input` through the effects into `output`, the window when `None`, or copies it
+    /// when there are no effects. Leaves depth testing disabled and `output` bound.
+    pub fn apply(&self, gl: &Context, input: Texture, output: Option<&RenderTarget>) {
+        unsafe {
+            gl.disable(DEPTH_TEST);
+            gl.active_texture(TEXTURE0);
+            gl.bind_vertex_array(Some(self.quad_vao.raw()));
+        }
+        let passes = self.effects.len().max(1);
+        let mut source = input;
+        for pass in 0..passes {
+            let effect = self.effects.get(pass);
+            let last = pass + 1 == passes;
+            let target = &self.targets[pass % 2];
+            match (last, output) {
+                (false, _) => target.bind(gl),
+                (true, Some(output)) => output.bind(gl),
+                (true, None) => unsafe {
+                    gl.bind_framebuffer(FRAMEBUFFER, None);
+                    gl.viewport(0, 0, target.width() as i32, target.height() as i32);
+                },
+            }
+
+            let shader = match effect {
+                Some(Effect::Custom(custom)) => self.custom_shader(*custom),
+                _ => {
+                    let defines = effect.and_then(Effect::defines).unwrap_or(&[]);
+                    self.shader
+                        .get(defines)
+                        .expect("Effect variants are compiled in new")
+                }
+            };
+            shader.use_shader(gl);
+            if let Some(Effect::Kernel(kernel)) = effect {
+                shader.set_float_array(gl, "kernel", &kernel.weights);
+                shader.set_float(gl, "offset", kernel.offset);
+            }
+            unsafe {
+                gl.bind_texture(TEXTURE_2D, Some(source));
+                gl.draw_arrays(TRIANGLES, 0, 6);
+            }
+            if let Some(texture) = target.color_texture(0) {
+                source = texture;
+            }
+        }
+        unsafe {
+            gl.bind_vertex_array(None);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use glow::{Context, HasContext, Program};
+use std::cell::Cell;
+use std::ffi::{c_void, CStr};
+use std::path::{Path, PathBuf};
+
+type GetProgramBinary = unsafe extern "system" fn(u32, i32, *mut i32, *mut u32, *mut c_void);
+type ProgramBinary = unsafe extern "system" fn(u32, u32, *const c_void, i32);
+type ProgramParameteri = unsafe extern "system" fn(u32, u32, i32);
+type GetProgramiv = unsafe extern "system" fn(u32, u32, *mut i32);
+
+/// The entry points glow doesn't wrap.
+#[derive(Clone, Copy)]
+struct Functions {
+    get_program_binary: GetProgramBinary,
+    program_binary: ProgramBinary,
+    program_parameteri: ProgramParameteri,
+    get_programiv: GetProgramiv,
+}
+
+thread_local! {
+    static FUNCTIONS: Cell<Option<Functions>> = const { Cell::new(None) };
+    /// loaded whether or not program binaries are supported
+    static GET_PROGRAMIV: Cell<Option<GetProgramiv>> = const { Cell::new(None) };
+}
+
+/// Loads the program binary functions with the loader the `glow::Context` was created with.
+/// Without them, e.g. on drivers that don't support program binaries, nothing is cached.
+pub(crate) unsafe fn load_functions(gl: &Context, mut loader: impl FnMut(&CStr) -> *const c_void) {
+    let get_programiv = loader(c"glGetProgramiv");
+    GET_PROGRAMIV.set(
+        (!get_programiv.is_null())
+            .then(|| std::mem::transmute::<*const c_void, GetProgramiv>(get_programiv)),
+    );
+    let version = gl.version();
+    let supported = if version.is_embedded {
+        version.major >= 3
+    } else {
+        (version.major, version.minor) >= (4, 1)
+            || gl
+                .supported_extensions()
+                .contains("GL_ARB_get_program_binary")
+    };
+    let functions = if supported && gl.get_parameter_i32(glow::NUM_PROGRAM_BINARY_FORMATS) > 0 {
+        let get_program_binary = loader(c"glGetProgramBinary");
+        let program_binary = loader(c"glProgramBinary");
+        let program_parameteri = loader(c"glProgramParameteri");
+        if [
+            get_program_binary,
+            program_binary,
+            program_parameteri,
+            get_programiv,
+        ]
+        .iter()
+        .any(|function| function.is_null())
+        {
+            None
+        } else {
+            Some(Functions {
+                get_program_binary: std::mem::transmute::<*const c_void, GetProgramBinary>(
+                    get_program_binary,
+                ),
+                program_binary: std::mem::transmute::<*const c_void, ProgramBinary>(program_binary),
+                program_parameteri: std::mem::transmute::<*const c_void, ProgramParameteri>(
+                    program_parameteri,
+                ),
+                get_programiv: std::mem::transmute::<*const c_void, GetProgramiv>(get_programiv),
+            })
+        }
+    } else {
+        None
+    };
+    FUNCTIONS.set(functions);
+}
+
+/// `glGetProgramiv` for the parameters glow has no getter for,
+/// `None` when `load_functions` didn't find it.
+pub(crate) unsafe fn program_parameter_i32(program: Program, parameter: u32) -> Option<i32> {
+    let get_programiv = GET_PROGRAMIV.get()?;
+    let mut value = 0;
+    get_programiv(program.0.get(), parameter, &mut value);
+    Some(value)
+}
+
+/// Where binaries go, `learn_opengl_rs/programs` in the temp directory unless
+/// `LEARN_OPENGL_PROGRAM_CACHE` names another directory or is `off`.
+/// `None` when caching is off or the functions aren't loaded.
+fn cache_dir() -> Option<PathBuf> {
+    FUNCTIONS.get()?;
+    match std::env::var("LEARN_OPENGL_PROGRAM_CACHE") {
+        Ok(dir) if dir == "off" => None,
+        Ok(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => Some(
+            std::env::temp_dir()
+                .join("learn_opengl_rs")
+                .join("programs"),
+        ),
+    }
+}
+
+/// Identifies a program by the sources of its stages and the driver that compiles them,
+/// so binaries of another driver or driver version are never tried.
+pub(crate) fn key(gl: &Context, stages: &[(u32, String)]) -> u64 {
+    let mut hash = Fnv1a::default();
+    for parameter in [glow::VENDOR, glow::RENDERER, glow::VERSION] {
+        hash.write_str(&unsafe { gl.get_parameter_string(parameter) });
+    }
+    for (stage, source) in stages {
+        hash.write(&stage.to_le_bytes());
+        hash.write_str(source);
+    }
+    hash.0
+}
+
+/// 64 bit FNV-1a, unlike `DefaultHasher` it gives the same keys with every Rust release,
+/// so binaries saved by an older build are still found.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// length first, so that ("ab", "c") and ("a", "bc") differ
+    fn write_str(&mut self, s: &str) {
+        self.write(&(s.len() as u64).to_le_bytes());
+        self.write(s.as_bytes());
+    }
+}
+
+/// Binaries kept in the cache directory, the least recently used go first. Every edit of a
+/// reloaded shader saves another one that is likely never read again.
+const MAX_ENTRIES: usize = 256;
+
+fn path(dir: &Path, key: u64) -> PathBuf {
+    dir.join(format!("{:016x}.bin", key))
+}
+
+/// A linked program from the binary saved for `key`, skipping compiling and linking,
+/// if there is one and the driver accepts it. Rejected binaries are deleted.
+pub(crate) unsafe fn load(gl: &Context, key: u64) -> Option<Program> {
+    let dir = cache_dir()?;
+    let functions = FUNCTIONS.get()?;
+    let path = path(&dir, key);
+    let bytes = std::fs::read(&path).ok()?;
+    if bytes.len() <= 4 {
+        return None;
+    }
+    let (format, binary) = bytes.split_at(4);
+    let format = u32::from_le_bytes(format.try_into().unwrap());
+
+    let program = gl.create_program().expect("Failed to create program");
+    (functions.program_binary)(
+        program.0.get(),
+        format,
+        binary.as_ptr() as *const c_void,
+        binary.len() as i32,
+    );
+    if gl.get_program_link_status(program) {
+        log::debug!("Loaded program binary {:?}", path);
+        // marks it as used for `evict`
+        let _ = std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(std::time::SystemTime::now()));
+        Some(program)
+    } else {
+        log::debug!("Driver rejected program binary {:?}", path);
+        gl.delete_program(program);
+        let _ = std::fs::remove_file(&path);
+        None
+    }
+}
+
+/// Asks the driver to keep the binary of `program`, call before linking it.
+pub(crate) unsafe fn prepare(program: Program) {
+    if let Some(functions) = FUNCTIONS.get() {
+        (functions.program_parameteri)(
+            program.0.get(),
+            glow::PROGRAM_BINARY_RETRIEVABLE_HINT,
+            glow::TRUE as i32,
+        );
+    }
+}
+
+/// Saves the binary of the linked `program` for `key`, failures only cost a relink next time.
+pub(crate) unsafe fn store(program: Program, key: u64) {
+    let (Some(dir), Some(functions)) = (cache_dir(), FUNCTIONS.get()) else {
+        return;
+    };
+    let mut length = 0;
+    (functions.get_programiv)(program.0.get(), glow::PROGRAM_BINARY_LENGTH, &mut length);
+    if length <= 0 {
+        return;
+    }
+    let mut binary = vec![0u8; length as usize];
+    let mut written = 0;
+    let mut format = 0;
+    (functions.get_program_binary)(
+        program.0.get(),
+        length,
+        &mut written,
+        &mut format,
+        binary.as_mut_ptr() as *mut c_void,
+    );
+    binary.truncate(written.max(0) as usize);
+    if binary.is_empty() {
+        return;
+    }
+    let mut bytes = format.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&binary);
+    let path = path(&dir, key);
+    // renamed into place so that other processes never read half a binary
+    let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+    let saved = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&temp, bytes))
+        .and_then(|_| std::fs::rename(&temp, &path));
+    if let Err(e) = saved {
+        log::warn!("Cannot save program binary {:?}: {:?}", path, e);
+        let _ = std::fs::remove_file(&temp);
+        return;
+    }
+    evict(&dir);
+}
+
+/// Deletes the binaries modified longest ago until at most `MAX_ENTRIES` are left.
+fn evict(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut binaries = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "bin" {
+                return None;
+            }
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .collect::<Vec<_>>();
+    if binaries.len() <= MAX_ENTRIES {
+        return;
+    }
+    binaries.sort();
+    let excess = binaries.len() - MAX_ENTRIES;
+    for (_, path) in binaries.into_iter().take(excess) {
+        log::debug!("Evicting program binary {:?}", path);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,368 @@
+use crate::_1_getting_started::*;
+use crate::_2_lighting::*;
+use crate::_3_model_loading::*;
+use crate::_4_advanced_opengl::*;
+use crate::_5_advanced_lighting::*;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+pub type TutorialFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Chapter {
+    GettingStarted,
+    Lighting,
+    ModelLoading,
+    AdvancedOpenGL,
+    AdvancedLighting,
+}
+
+impl Chapter {
+    pub const ALL: [Chapter; 5] = [
+        Chapter::GettingStarted,
+        Chapter::Lighting,
+        Chapter::ModelLoading,
+        Chapter::AdvancedOpenGL,
+        Chapter::AdvancedLighting,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Chapter::GettingStarted => "Getting Started",
+            Chapter::Lighting => "Lighting",
+            Chapter::ModelLoading => "Model Loading",
+            Chapter::AdvancedOpenGL => "Advanced OpenGL",
+            Chapter::AdvancedLighting => "Advanced Lighting",
+        }
+    }
+}
+
+impl fmt::Display for Chapter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.title())
+    }
+}
+
+/// GL features a tutorial needs beyond what every platform offers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GlFeature {
+    GeometryShader,
+    InterfaceBlocks,
+}
+
+impl GlFeature {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GlFeature::GeometryShader => "geometry shaders",
+            GlFeature::InterfaceBlocks => "interface blocks",
+        }
+    }
+
+    /// WebGL2 is GLES 3.0, which has neither geometry shaders nor interface blocks between stages.
+    pub fn supported_on(&self, platform: Platform) -> bool {
+        platform != Platform::Web
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Desktop,
+    /// WebGL2 in the browser
+    Web,
+    /// offscreen rendering with `--headless`
+    Headless,
+}
+
+impl Platform {
+    pub const ALL: &'static [Platform] = &[Platform::Desktop, Platform::Web, Platform::Headless];
+
+    /// The platform `run_tutorial` is about to run on.
+    pub fn current() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            Platform::Web
+        } else if crate::window::launched_headless() {
+            Platform::Headless
+        } else {
+            Platform::Desktop
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::Desktop => "desktop",
+            Platform::Web => "WebGL2",
+            Platform::Headless => "headless",
+        }
+    }
+}
+
+pub struct Tutorial {
+    /// e.g. `1_2_1`, accepted by `run_tutorial`
+    pub id: &'static str,
+    pub chapter: Chapter,
+    pub title: &'static str,
+    pub features: &'static [GlFeature],
+    pub platforms: &'static [Platform],
+    /// creates its own window instead of going through `window::run`, so it can't be hosted
+    /// by the launcher or rendered headless
+    pub own_window: bool,
+    main: fn() -> TutorialFuture,
+}
+
+impl Tutorial {
+    const fn new(
+        id: &'static str,
+        chapter: Chapter,
+        title: &'static str,
+        main: fn() -> TutorialFuture,
+    ) -> Self {
+        Self {
+            id,
+            chapter,
+            title,
+            features: &[],
+            platforms: Platform::ALL,
+            own_window: false,
+            main,
+        }
+    }
+
+    const fn requires(mut self, features: &'static [GlFeature]) -> Self {
+        self.features = features;
+        self
+    }
+
+    const fn with_own_window(mut self) -> Self {
+        self.own_window = true;
+        self.platforms = &[Platform::Desktop, Platform::Web];
+        self
+    }
+
+    /// Why the tutorial can't run on `platform`, `None` if it can.
+    pub fn unsupported_reason(&self, platform: Platform) -> Option<String> {
+        if !self.platforms.contains(&platform) {
+            return Some(format!("is not available on {}", platform.name()));
+        }
+        let missing: Vec<_> = self
+            .features
+            .iter()
+            .filter(|feature| !feature.supported_on(platform))
+            .map(|feature| feature.name())
+            .collect();
+        if !missing.is_empty() {
+            return Some(format!(
+                "needs {}, which {} doesn't support",
+                missing.join(" and "),
+                platform.name()
+            ));
+        }
+        None
+    }
+
+    pub fn supports(&self, platform: Platform) -> bool {
+        self.unsupported_reason(platform).is_none()
+    }
+
+    /// # Safety
+    /// Runs raw GL calls, see `window::run`.
+    pub unsafe fn run(&self) -> TutorialFuture {
+        (self.main)()
+    }
+}
+
+impl fmt::Debug for Tutorial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tutorial")
+            .field("id", &self.id)
+            .field("chapter", &self.chapter)
+            .field("title", &self.title)
+            .field("features", &self.features)
+            .field("platforms", &self.platforms)
+            .field("own_window", &self.own_window)
+            .finish()
+    }
+}
+
+macro_rules! tutorial {
+    ($id:literal, $chapter:ident, $title:literal, $main:ident) => {
+        Tutorial::new($id, Chapter::$chapter, $title, || {
+            Box::pin(unsafe { $main() })
+        })
+    };
+}
+
+use GlFeature::*;
+
+/// Every tutorial in chapter order.
+pub static TUTORIALS: [Tutorial; 77] = [
+    tutorial!("1_1_1", GettingStarted, "Hello Window", main_1_1_1).with_own_window(),
+    tutorial!("1_1_2", GettingStarted, "Hello Window Clear", main_1_1_2),
+    tutorial!("1_2_1", GettingStarted, "Hello Triangle", main_1_2_1),
+    tutorial!(
+        "1_2_2",
+        GettingStarted,
+        "Hello Triangle Indexed",
+        main_1_2_2
+    ),
+    tutorial!(
+        "1_2_3",
+        GettingStarted,
+        "Hello Triangle Exercise 1",
+        main_1_2_3
+    ),
+    tutorial!(
+        "1_2_4",
+        GettingStarted,
+        "Hello Triangle Exercise 2",
+        main_1_2_4
+    ),
+    tutorial!(
+        "1_2_5",
+        GettingStarted,
+        "Hello Triangle Exercise 3",
+        main_1_2_5
+    ),
+    tutorial!("1_3_1", GettingStarted, "Shaders Uniform", main_1_3_1),
+    tutorial!("1_3_2", GettingStarted, "Shaders Interpolation", main_1_3_2),
+    tutorial!("1_3_3", GettingStarted, "Shaders Class", main_1_3_3),
+    tutorial!("1_3_4", GettingStarted, "Shaders Exercise 1", main_1_3_4),
+    tutorial!("1_3_5", GettingStarted, "Shaders Exercise 2", main_1_3_5),
+    tutorial!("1_3_6", GettingStarted, "Shaders Exercise 3", main_1_3_6),
+    tutorial!("1_4_1", GettingStarted, "Textures", main_1_4_1),
+    tutorial!("1_4_2", GettingStarted, "Textures Combined", main_1_4_2),
+    tutorial!("1_4_3", GettingStarted, "Textures Exercise 1", main_1_4_3),
+    tutorial!("1_4_4", GettingStarted, "Textures Exercise 2", main_1_4_4),
+    tutorial!("1_4_5", GettingStarted, "Textures Exercise 3", main_1_4_5),
+    tutorial!("1_4_6", GettingStarted, "Textures Exercise 4", main_1_4_6),
+    tutorial!("1_5_1", GettingStarted, "Transformations", main_1_5_1),
+    tutorial!(
+        "1_5_2",
+        GettingStarted,
+        "Transformations Exercise 1",
+        main_1_5_2
+    ),
+    tutorial!(
+        "1_5_3",
+        GettingStarted,
+        "Transformations Exercise 2",
+        main_1_5_3
+    ),
+    tutorial!("1_6_1", GettingStarted, "Coordinate Systems", main_1_6_1),
+    tutorial!(
+        "1_6_2",
+        GettingStarted,
+        "Coordinate Systems Depth",
+        main_1_6_2
+    ),
+    tutorial!(
+        "1_6_3",
+        GettingStarted,
+        "Coordinate Systems Multiple",
+        main_1_6_3
+    ),
+    tutorial!(
+        "1_6_4",
+        GettingStarted,
+        "Coordinate Systems Exercise",
+        main_1_6_4
+    ),
+    tutorial!("1_7_1", GettingStarted, "Camera Circle", main_1_7_1),
+    tutorial!("1_7_2", GettingStarted, "Camera Keyboard Dt", main_1_7_2),
+    tutorial!("1_7_3", GettingStarted, "Camera Mouse Zoom", main_1_7_3),
+    tutorial!("1_7_4", GettingStarted, "Camera Class", main_1_7_4),
+    tutorial!("1_7_5", GettingStarted, "Camera Exercise 1", main_1_7_5),
+    tutorial!("1_7_6", GettingStarted, "Camera Exercise 2", main_1_7_6),
+    tutorial!("2_1_1", Lighting, "Colors", main_2_1_1),
+    tutorial!("2_2_1", Lighting, "Basic Lighting Diffuse", main_2_2_1),
+    tutorial!("2_2_2", Lighting, "Basic Lighting Specular", main_2_2_2),
+    tutorial!("2_2_3", Lighting, "Basic Lighting Exercise 1", main_2_2_3),
+    tutorial!("2_2_4", Lighting, "Basic Lighting Exercise 2", main_2_2_4),
+    tutorial!("2_2_5", Lighting, "Basic Lighting Exercise 3", main_2_2_5),
+    tutorial!("2_3_1", Lighting, "Materials", main_2_3_1),
+    tutorial!("2_3_2", Lighting, "Materials Exercise 1", main_2_3_2),
+    tutorial!("2_4_1", Lighting, "Lighting Maps Diffuse Map", main_2_4_1),
+    tutorial!("2_4_2", Lighting, "Lighting Maps Specular Map", main_2_4_2),
+    tutorial!("2_4_3", Lighting, "Lighting Maps Exercise 2", main_2_4_3),
+    tutorial!("2_4_4", Lighting, "Lighting Maps Exercise 3", main_2_4_4),
+    tutorial!("2_4_5", Lighting, "Lighting Maps Exercise 4", main_2_4_5),
+    tutorial!("2_5_1", Lighting, "Light Casters Directional", main_2_5_1),
+    tutorial!("2_5_2", Lighting, "Light Casters Point", main_2_5_2),
+    tutorial!("2_5_3", Lighting, "Light Casters Spot", main_2_5_3),
+    tutorial!("2_5_4", Lighting, "Light Casters Spot Soft", main_2_5_4),
+    tutorial!("2_6_1", Lighting, "Multiple Lights", main_2_6_1),
+    tutorial!("2_6_2", Lighting, "Multiple Lights Exercise 1", main_2_6_2),
+    tutorial!("3_1_1", ModelLoading, "Model Loading", main_3_1_1),
+    tutorial!("4_1_1", AdvancedOpenGL, "Depth Testing", main_4_1_1),
+    tutorial!("4_1_2", AdvancedOpenGL, "Depth Testing View", main_4_1_2),
+    tutorial!("4_2_1", AdvancedOpenGL, "Stencil Testing", main_4_2_1),
+    tutorial!("4_3_1", AdvancedOpenGL, "Blending Discard", main_4_3_1),
+    tutorial!("4_3_2", AdvancedOpenGL, "Blending Sort", main_4_3_2),
+    tutorial!(
+        "4_4_1",
+        AdvancedOpenGL,
+        "Face Culling Exercise 1",
+        main_4_4_1
+    ),
+    tutorial!("4_5_1", AdvancedOpenGL, "Framebuffers", main_4_5_1),
+    tutorial!("4_5_2", AdvancedOpenGL, "Framebuffers Effects", main_4_5_2),
+    tutorial!(
+        "4_5_3",
+        AdvancedOpenGL,
+        "Framebuffers Exercise 1",
+        main_4_5_3
+    ),
+    tutorial!("4_6_1", AdvancedOpenGL, "Cubemaps Skybox", main_4_6_1),
+    tutorial!(
+        "4_6_2",
+        AdvancedOpenGL,
+        "Cubemaps Environment Mapping",
+        main_4_6_2
+    ),
+    tutorial!("4_8_1", AdvancedOpenGL, "Advanced GLSL Ubo", main_4_8_1),
+    tutorial!(
+        "4_9_1",
+        AdvancedOpenGL,
+        "Geometry Shader Houses",
+        main_4_9_1
+    )
+    .requires(&[GeometryShader, InterfaceBlocks]),
+    tutorial!(
+        "4_9_2",
+        AdvancedOpenGL,
+        "Geometry Shader Exploding",
+        main_4_9_2
+    )
+    .requires(&[GeometryShader, InterfaceBlocks]),
+    tutorial!(
+        "4_9_3",
+        AdvancedOpenGL,
+        "Geometry Shader Normals",
+        main_4_9_3
+    )
+    .requires(&[GeometryShader, InterfaceBlocks]),
+    tutorial!("4_10_1", AdvancedOpenGL, "Instancing Quads", main_4_10_1),
+    tutorial!("4_10_2", AdvancedOpenGL, "Asteroids", main_4_10_2),
+    tutorial!("4_10_3", AdvancedOpenGL, "Asteroids Instanced", main_4_10_3),
+    tutorial!("4_11_1", AdvancedOpenGL, "Anti Aliasing MSAA", main_4_11_1),
+    tutorial!(
+        "4_11_2",
+        AdvancedOpenGL,
+        "Anti Aliasing Offscreen",
+        main_4_11_2
+    ),
+    tutorial!("5_1_1", AdvancedLighting, "Advanced Lighting", main_5_1_1),
+    tutorial!("5_2_1", AdvancedLighting, "Gamma Correction", main_5_2_1),
+    tutorial!(
+        "5_3_1",
+        AdvancedLighting,
+        "Shadow Mapping Depth",
+        main_5_3_1
+    ),
+    tutorial!("5_3_2", AdvancedLighting, "Shadow Mapping Base", main_5_3_2),
+    tutorial!("5_3_3", AdvancedLighting, "Shadow Mapping", main_5_3_3),
+];
+
+pub fn find_tutorial(id: &str) -> Option<&'static Tutorial> {
+    TUTORIALS.iter().find(|tutorial| tutorial.id == id)
+}
@@ -0,0 +1,398 @@
+use crate::gl_object::{GlFramebuffer, GlRenderbuffer, GlTexture};
+use crate::window::SharedGl;
+use glow::*;
+use std::fmt;
+
+/// Format of a color attachment, the `internal_format` alone for multisampled renderbuffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorFormat {
+    pub internal_format: u32,
+    pub format: u32,
+    pub ty: u32,
+}
+
+impl ColorFormat {
+    pub const RGB8: Self = Self::new(RGB8, RGB, UNSIGNED_BYTE);
+    pub const RGBA8: Self = Self::new(RGBA8, RGBA, UNSIGNED_BYTE);
+    pub const RGBA16F: Self = Self::new(RGBA16F, RGBA, HALF_FLOAT);
+    pub const RGBA32F: Self = Self::new(RGBA32F, RGBA, FLOAT);
+
+    pub const fn new(internal_format: u32, format: u32, ty: u32) -> Self {
+        Self {
+            internal_format,
+            format,
+            ty,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepthAttachment {
+    None,
+    /// a `DEPTH24_STENCIL8` renderbuffer, for depth and stencil testing without sampling them
+    DepthStencil,
+    /// a `DEPTH_COMPONENT24` texture to sample, e.g. a shadow map
+    Texture,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderTargetError {
+    /// creating a GL object failed
+    Create(String),
+    /// the attachments don't make a complete framebuffer
+    Incomplete(u32),
+    /// a multisampled target can't have a depth texture
+    MultisampledDepthTexture,
+}
+
+impl fmt::Display for RenderTargetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Create(e) => write!(f, "Failed to create render target: {}", e),
+            Self::Incomplete(status) => {
+                let reason = match *status {
+                    FRAMEBUFFER_UNDEFINED => "FRAMEBUFFER_UNDEFINED",
+                    FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "FRAMEBUFFER_INCOMPLETE_ATTACHMENT, an attachment's format can't be rendered to",
+                    FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => "FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT, nothing is attached",
+                    FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => "FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER",
+                    FRAMEBUFFER_INCOMPLETE_READ_BUFFER => "FRAMEBUFFER_INCOMPLETE_READ_BUFFER",
+                    FRAMEBUFFER_UNSUPPORTED => "FRAMEBUFFER_UNSUPPORTED, the driver doesn't support this combination of formats",
+                    FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => "FRAMEBUFFER_INCOMPLETE_MULTISAMPLE, the attachments have different sample counts",
+                    FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => "FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS",
+                    _ => "unknown status",
+                };
+                write!(
+                    f,
+                    "Framebuffer is not complete: {} (0x{:X})",
+                    reason, status
+                )
+            }
+            Self::MultisampledDepthTexture => {
+                write!(f, "A multisampled render target can't have a depth texture")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenderTargetError {}
+
+/// Describes a `RenderTarget`, e.g. `RenderTarget::builder(width, height).color(ColorFormat::RGB8)
+/// .depth(DepthAttachment::DepthStencil).build(gl)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RenderTargetBuilder {
+    width: i32,
+    height: i32,
+    colors: Vec<ColorFormat>,
+    depth: DepthAttachment,
+    samples: i32,
+    filter: u32,
+    wrap: u32,
+}
+
+impl RenderTargetBuilder {
+    /// Adds a color attachment, `COLOR_ATTACHMENT0` first and `location = 0` in the fragment shader.
+    pub fn color(mut self, format: ColorFormat) -> Self {
+        self.colors.push(format);
+        self
+    }
+
+    pub fn depth(mut self, depth: DepthAttachment) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Renders into multisampled renderbuffers instead of textures, resolve them with `blit_to`.
+    /// Clamped to `MAX_SAMPLES`.
+    pub fn samples(mut self, samples: i32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Min and mag filter of the textures, `LINEAR` by default.
+    pub fn filter(mut self, filter: u32) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Wrap mode of the textures, `REPEAT` by default like any new texture.
+    pub fn wrap(mut self, wrap: u32) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn build(self, gl: &SharedGl) -> Result<RenderTarget, RenderTargetError> {
+        if self.samples > 0 && self.depth == DepthAttachment::Texture {
+            return Err(RenderTargetError::MultisampledDepthTexture);
+        }
+        let framebuffer = GlFramebuffer::new(gl).map_err(RenderTargetError::Create)?;
+        let attachments = unsafe { Attachments::new(gl, &self)? };
+        unsafe { attachments.attach(gl, &framebuffer)? };
+        Ok(RenderTarget {
+            framebuffer,
+            attachments,
+            builder: self,
+        })
+    }
+}
+
+/// An FBO with its attachments, sized like the window or fixed like a shadow map.
+/// Call `resize` from `Application::resize` to keep it at the window size.
+#[derive(Debug)]
+pub struct RenderTarget {
+    framebuffer: GlFramebuffer,
+    attachments: Attachments,
+    builder: RenderTargetBuilder,
+}
+
+impl RenderTarget {
+    pub fn builder(width: u32, height: u32) -> RenderTargetBuilder {
+        RenderTargetBuilder {
+            width: width as i32,
+            height: height as i32,
+            colors: Vec::new(),
+            depth: DepthAttachment::None,
+            samples: 0,
+            filter: LINEAR,
+            wrap: REPEAT,
+        }
+    }
+
+    /// Recreates the attachments at the new size, their contents are lost.
+    /// Does nothing if the size didn't change or is 0, e.g. for a minimized window.
+    /// On error the target keeps its old size and attachments.
+    pub fn resize(
+        &mut self,
+        gl: &SharedGl,
+        width: u32,
+        height: u32,
+    ) -> Result<(), RenderTargetError> {
+        let (width, height) = (width as i32, height as i32);
+        if (width, height) == (self.builder.width, self.builder.height) || width == 0 || height == 0
+        {
+            return Ok(());
+        }
+        let builder = RenderTargetBuilder {
+            width,
+            height,
+            ..self.builder.clone()
+        };
+        let attachments = unsafe { Attachments::new(gl, &builder)? };
+        if let Err(e) = unsafe { attachments.attach(gl, &self.framebuffer) } {
+            // the new attachments are deleted with `attachments`, point the framebuffer back
+            // at the old ones, which were complete before
+            let _ = unsafe { self.attachments.attach(gl, &self.framebuffer) };
+            return Err(e);
+        }
+        self.builder = builder;
+        self.attachments = attachments;
+        Ok(())
+    }
+
+    /// Binds the framebuffer and sets the viewport to its size.
+    pub fn bind(&self, gl: &Context) {
+        unsafe {
+            gl.bind_framebuffer(FRAMEBUFFER, Some(self.framebuffer.raw()));
+            gl.viewport(0, 0, self.builder.width, self.builder.height);
+        }
+    }
+
+    /// Copies the first color attachment into the one of `target`, scaled to its size.
+    /// This resolves a multisampled target into a texture to sample, both need the same size then.
+    pub fn blit_to(&self, gl: &Context, target: &RenderTarget) {
+        unsafe {
+            gl.bind_framebuffer(READ_FRAMEBUFFER, Some(self.framebuffer.raw()));
+            gl.bind_framebuffer(DRAW_FRAMEBUFFER, Some(target.framebuffer.raw()));
+            gl.blit_framebuffer(
+                0,
+                0,
+                self.builder.width,
+                self.builder.height,
+                0,
+                0,
+                target.builder.width,
+                target.builder.height,
+                COLOR_BUFFER_BIT,
+                NEAREST,
+            );
+            gl.bind_framebuffer(FRAMEBUFFER, None);
+        }
+    }
+
+    /// Texture of color attachment `index`, `None` for a multisampled target.
+    pub fn color_texture(&self, index: usize) -> Option<glow::Texture> {
+        self.attachments
+            .color_textures
+            .get(index)
+            .map(|texture| texture.raw())
+    }
+
+    pub fn depth_texture(&self) -> Option<glow::Texture> {
+        self.attachments
+            .depth_texture
+            .as_ref()
+            .map(|texture| texture.raw())
+    }
+
+    pub fn framebuffer(&self) -> Framebuffer {
+        self.framebuffer.raw()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.builder.width as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.builder.height as u32
+    }
+}
+
+/// Everything attached to the framebuffer, replaced as a whole on resize.
+#[derive(Debug)]
+struct Attachments {
+    /// textures, or renderbuffers for a multisampled target, in attachment order
+    color_textures: Vec<GlTexture>,
+    color_renderbuffers: Vec<GlRenderbuffer>,
+    depth_renderbuffer: Option<GlRenderbuffer>,
+    depth_texture: Option<GlTexture>,
+}
+
+impl Attachments {
+    /// Creates the attachments of `builder`, nothing is bound when this returns, not even on error.
+    unsafe fn new(gl: &SharedGl, builder: &RenderTargetBuilder) -> Result<Self, RenderTargetError> {
+        let RenderTargetBuilder {
+            width,
+            height,
+            ref colors,
+            depth,
+            samples,
+            filter,
+            wrap,
+        } = *builder;
+        let samples = samples.min(gl.get_parameter_i32(MAX_SAMPLES));
+        let mut attachments = Self {
+            color_textures: Vec::new(),
+            color_renderbuffers: Vec::new(),
+            depth_renderbuffer: None,
+            depth_texture: None,
+        };
+        let texture =
+            |internal_format: u32, format: u32, ty: u32| -> Result<GlTexture, RenderTargetError> {
+                let texture = GlTexture::new(gl).map_err(RenderTargetError::Create)?;
+                gl.bind_texture(TEXTURE_2D, Some(texture.raw()));
+                gl.tex_image_2d(
+                    TEXTURE_2D,
+                    0,
+                    internal_format as i32,
+                    width,
+                    height,
+                    0,
+                    format,
+                    ty,
+                    None,
+                );
+                gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MIN_FILTER, filter as i32);
+                gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MAG_FILTER, filter as i32);
+                gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_S, wrap as i32);
+                gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_T, wrap as i32);
+                gl.bind_texture(TEXTURE_2D, None);
+                Ok(texture)
+            };
+        let renderbuffer = |internal_format: u32| -> Result<GlRenderbuffer, RenderTargetError> {
+            let renderbuffer = GlRenderbuffer::new(gl).map_err(RenderTargetError::Create)?;
+            gl.bind_renderbuffer(RENDERBUFFER, Some(renderbuffer.raw()));
+            if samples > 0 {
+                gl.renderbuffer_storage_multisample(
+                    RENDERBUFFER,
+                    samples,
+                    internal_format,
+                    width,
+                    height,
+                );
+            } else {
+                gl.renderbuffer_storage(RENDERBUFFER, internal_format, width, height);
+            }
+            gl.bind_renderbuffer(RENDERBUFFER, None);
+            Ok(renderbuffer)
+        };
+
+        for format in colors {
+            if samples > 0 {
+                let renderbuffer = renderbuffer(format.internal_format)?;
+                attachments.color_renderbuffers.push(renderbuffer);
+            } else {
+                let texture = texture(format.internal_format, format.format, format.ty)?;
+                attachments.color_textures.push(texture);
+            }
+        }
+        match depth {
+            DepthAttachment::None => {}
+            DepthAttachment::DepthStencil => {
+                attachments.depth_renderbuffer = Some(renderbuffer(DEPTH24_STENCIL8)?);
+            }
+            DepthAttachment::Texture => {
+                // WebGL2 only takes sized depth formats and UNSIGNED_INT data for them
+                let texture = texture(DEPTH_COMPONENT24, DEPTH_COMPONENT, UNSIGNED_INT)?;
+                attachments.depth_texture = Some(texture);
+            }
+        }
+        Ok(attachments)
+    }
+
+    /// Attaches everything to `framebuffer`, which is unbound afterwards, also when it's incomplete.
+    unsafe fn attach(
+        &self,
+        gl: &Context,
+        framebuffer: &GlFramebuffer,
+    ) -> Result<(), RenderTargetError> {
+        gl.bind_framebuffer(FRAMEBUFFER, Some(framebuffer.raw()));
+        let mut draw_buffers = Vec::new();
+        for (i, texture) in self.color_textures.iter().enumerate() {
+            let attachment = COLOR_ATTACHMENT0 + i as u32;
+            gl.framebuffer_texture_2d(FRAMEBUFFER, attachment, TEXTURE_2D, Some(texture.raw()), 0);
+            draw_buffers.push(attachment);
+        }
+        for (i, renderbuffer) in self.color_renderbuffers.iter().enumerate() {
+            let attachment = COLOR_ATTACHMENT0 + i as u32;
+            gl.framebuffer_renderbuffer(
+                FRAMEBUFFER,
+                attachment,
+                RENDERBUFFER,
+                Some(renderbuffer.raw()),
+            );
+            draw_buffers.push(attachment);
+        }
+        if draw_buffers.is_empty() {
+            // a depth only target, e.g. a shadow map
+            gl.draw_buffers(&[NONE]);
+            gl.read_buffer(NONE);
+        } else {
+            gl.draw_buffers(&draw_buffers);
+            gl.read_buffer(COLOR_ATTACHMENT0);
+        }
+
+        if let Some(renderbuffer) = &self.depth_renderbuffer {
+            gl.framebuffer_renderbuffer(
+                FRAMEBUFFER,
+                DEPTH_STENCIL_ATTACHMENT,
+                RENDERBUFFER,
+                Some(renderbuffer.raw()),
+            );
+        }
+        if let Some(texture) = &self.depth_texture {
+            gl.framebuffer_texture_2d(
+                FRAMEBUFFER,
+                DEPTH_ATTACHMENT,
+                TEXTURE_2D,
+                Some(texture.raw()),
+                0,
+            );
+        }
+
+        let status = gl.check_framebuffer_status(FRAMEBUFFER);
+        gl.bind_framebuffer(FRAMEBUFFER, None);
+        if status != FRAMEBUFFER_COMPLETE {
+            return Err(RenderTargetError::Incomplete(status));
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 use crate::mesh::{Material, Mesh, Vertex};
 use crate::model::Model;
-use crate::texture::{Texture, TextureType};
+use crate::texture::{CubemapTexture, Texture, TextureOptions, TextureType};
+use crate::window::SharedGl;
 use cfg_if::cfg_if;
-use glow::Context;
 use nalgebra_glm as glm;
 use std::io::{BufReader, Cursor};
 use std::path::Path;
@@ -20,6 +20,20 @@ fn format_url(file_name: &str) -> reqwest::Url {
     base.join(file_name).unwrap()
 }
 
+/// Where `file_name` lives in the source tree, what build.rs copied next to the binary.
+/// `shaders/<chapter>/<file>` maps back to `src/<chapter>/shaders/<file>`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn source_path(file_name: &str) -> std::path::PathBuf {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    match file_name
+        .strip_prefix("shaders/")
+        .and_then(|rest| rest.split_once('/'))
+    {
+        Some((chapter, file)) => root.join("src").join(chapter).join("shaders").join(file),
+        None => root.join("resources").join(file_name),
+    }
+}
+
 #[allow(dead_code)]
 pub async fn load_string(file_name: &str) -> anyhow::Result<String> {
     cfg_if! {
@@ -70,22 +84,55 @@ pub async fn load_binary(file_name: &str) -> anyhow::Result<Vec<u8>> {
 }
 
 pub async fn load_texture_with_type(
-    gl: &Context,
+    gl: &SharedGl,
     file_name: &str,
     ty: TextureType,
 ) -> anyhow::Result<Texture> {
     log::info!("Loading texture ty: {:?}, file_name: {}", ty, file_name);
     let data = load_binary(file_name).await?;
-    Texture::from_bytes(gl, &data, file_name, ty)
+    Texture::from_bytes(gl, &data, file_name, ty, &TextureOptions::default())
+}
+
+pub async fn load_texture(gl: &SharedGl, file_name: &str) -> anyhow::Result<Texture> {
+    load_texture_with_options(gl, file_name, &TextureOptions::default()).await
+}
+
+/// Like `load_texture`, with other wrap modes, filters or formats than the defaults.
+pub async fn load_texture_with_options(
+    gl: &SharedGl,
+    file_name: &str,
+    options: &TextureOptions,
+) -> anyhow::Result<Texture> {
+    log::info!(
+        "Loading texture file_name: {}, options: {:?}",
+        file_name,
+        options
+    );
+    let data = load_binary(file_name).await?;
+    Texture::from_bytes(gl, &data, file_name, TextureType::Diffuse, options)
 }
 
-pub async fn load_texture(gl: &Context, file_name: &str) -> anyhow::Result<Texture> {
-    log::info!("Loading texture file_name: {}", file_name);
+/// Loads the six faces of a cubemap: right, left, top, bottom, front and back.
+pub async fn load_cubemap(gl: &SharedGl, faces: &[&str; 6]) -> anyhow::Result<CubemapTexture> {
+    log::info!("Loading cubemap faces: {:?}", faces);
+    let mut images = Vec::new();
+    for face in faces {
+        let data = load_binary(face).await?;
+        images.push(image::load_from_memory(&data)?);
+    }
+    let images: [image::DynamicImage; 6] = images.try_into().unwrap();
+    CubemapTexture::from_faces(gl, &images)
+}
+
+/// Loads a cubemap from a single cross or equirectangular image, see `CubemapTexture::from_image`.
+#[allow(dead_code)]
+pub async fn load_cubemap_image(gl: &SharedGl, file_name: &str) -> anyhow::Result<CubemapTexture> {
+    log::info!("Loading cubemap file_name: {}", file_name);
     let data = load_binary(file_name).await?;
-    Texture::from_bytes(gl, &data, file_name, TextureType::Diffuse)
+    CubemapTexture::from_image(gl, &image::load_from_memory(&data)?)
 }
 
-pub async fn load_obj(gl: &Context, file_name: &str) -> anyhow::Result<Model> {
+pub async fn load_obj(gl: &SharedGl, file_name: &str) -> anyhow::Result<Model> {
     let obj_text = load_string(file_name).await?;
     let obj_cursor = Cursor::new(obj_text);
     let mut obj_reader = BufReader::new(obj_cursor);
@@ -1,220 +1,1074 @@
+use crate::gl_object::GlProgram;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::program_cache;
 use crate::resources;
-use glow::{Context, HasContext, Program, FRAGMENT_SHADER, GEOMETRY_SHADER, VERTEX_SHADER};
+use crate::shader_reflection::{self, ShaderReflection};
+use crate::window::SharedGl;
+use glow::{
+    Context, HasContext, Program, UniformLocation, COMPUTE_SHADER, FRAGMENT_SHADER,
+    GEOMETRY_SHADER, PATCH_VERTICES, TESS_CONTROL_SHADER, TESS_EVALUATION_SHADER, VERTEX_SHADER,
+};
+use nalgebra_glm as glm;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::SystemTime;
 
 pub struct MyShader {
     name: Option<String>,
-    program: Program,
+    program: GlProgram,
+    /// active uniforms by name, queried once after linking
+    uniforms: HashMap<String, UniformInfo>,
+    /// uniforms an edited source left inactive after a reload, skipped instead of panicking
+    dropped_uniforms: HashSet<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    watcher: Option<ShaderWatcher>,
+}
+
+/// Generates a `set_*` function, which panics on a missing or mismatched uniform like
+/// `uniform_location`, and its `try_set_*` counterpart, which returns whether the uniform was set
+/// and only warns about a mismatch.
+/// Array setters write `len` elements starting at the named one, e.g. `lights` or `lights[2]`.
+macro_rules! uniform_setters {
+    ($(
+        $set:ident, $try_set:ident($value:ident: $ty:ty) as $kind:ident $(* $count:expr)?,
+        |$gl:ident, $location:ident| $body:block;
+    )*) => {
+        $(
+            #[allow(dead_code)]
+            pub fn $set(&self, $gl: &Context, name: &str, $value: $ty) {
+                let count = 1 $(* $count as i32)?;
+                if let Some($location) = self.uniform_location(name, UniformKind::$kind, count) {
+                    let $location = Some($location);
+                    unsafe { $body }
+                }
+            }
+
+            #[allow(dead_code)]
+            pub fn $try_set(&self, $gl: &Context, name: &str, $value: $ty) -> bool {
+                let count = 1 $(* $count as i32)?;
+                match self.try_uniform_location(name, UniformKind::$kind, count) {
+                    Some($location) => {
+                        let $location = Some($location);
+                        unsafe { $body }
+                        true
+                    }
+                    None => false,
+                }
+            }
+        )*
+    };
+}
+
+/// An active uniform of a linked program, array elements get an entry each,
+/// e.g. `lights[0]`, `lights[1]` and `lights` for the whole array.
+#[derive(Debug, Clone)]
+struct UniformInfo {
+    location: UniformLocation,
+    utype: u32,
+    /// elements from this one to the end of the array, 1 for plain uniforms
+    size: i32,
+}
+
+/// What a `set_*` function writes, checked against the GLSL type of the uniform.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum UniformKind {
+    Bool,
+    Int,
+    Uint,
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+    IVec2,
+    IVec3,
+    IVec4,
+    Mat3,
+    Mat4,
+}
+
+impl UniformKind {
+    /// follows the glUniform* rules, e.g. `uniform1i` also sets bools and samplers
+    fn accepts(self, utype: u32) -> bool {
+        match self {
+            Self::Bool | Self::Int => matches!(utype, glow::INT | glow::BOOL) || is_sampler(utype),
+            Self::Uint => matches!(utype, glow::UNSIGNED_INT | glow::BOOL),
+            Self::Float => matches!(utype, glow::FLOAT | glow::BOOL),
+            Self::Vec2 => matches!(utype, glow::FLOAT_VEC2 | glow::BOOL_VEC2),
+            Self::Vec3 => matches!(utype, glow::FLOAT_VEC3 | glow::BOOL_VEC3),
+            Self::Vec4 => matches!(utype, glow::FLOAT_VEC4 | glow::BOOL_VEC4),
+            Self::IVec2 => matches!(utype, glow::INT_VEC2 | glow::BOOL_VEC2),
+            Self::IVec3 => matches!(utype, glow::INT_VEC3 | glow::BOOL_VEC3),
+            Self::IVec4 => matches!(utype, glow::INT_VEC4 | glow::BOOL_VEC4),
+            Self::Mat3 => utype == glow::FLOAT_MAT3,
+            Self::Mat4 => utype == glow::FLOAT_MAT4,
+        }
+    }
+}
+
+/// Source files of a shader created by `MyShader::new`, see `MyShader::reload_if_changed`.
+#[cfg(not(target_arch = "wasm32"))]
+struct ShaderWatcher {
+    vertex_path: String,
+    fragment_path: String,
+    shader_version: Option<String>,
+    /// `#define`s of every stage, e.g. of a `ShaderVariants` variant
+    defines: Vec<(String, String)>,
+    /// both stages and everything they include
+    files: Vec<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ShaderWatcher {
+    fn new(
+        vertex: &PreprocessedSource,
+        fragment: &PreprocessedSource,
+        shader_version: Option<&str>,
+        defines: &[(String, String)],
+    ) -> Self {
+        let mut watcher = Self {
+            vertex_path: vertex.files[0].clone(),
+            fragment_path: fragment.files[0].clone(),
+            shader_version: shader_version.map(str::to_string),
+            defines: defines.to_vec(),
+            files: vec![],
+            modified: None,
+        };
+        watcher.watch(vertex, fragment);
+        watcher
+    }
+
+    fn watch(&mut self, vertex: &PreprocessedSource, fragment: &PreprocessedSource) {
+        self.files = vertex
+            .files
+            .iter()
+            .chain(&fragment.files)
+            .map(|file| resources::source_path(file))
+            .collect();
+        self.modified = self.last_modified();
+    }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        self.files
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .max()
+    }
+}
+
+/// A shader source with its `#include "file"` directives expanded, see `MyShader::load_source`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreprocessedSource {
+    pub source: String,
+    /// resource paths by GLSL source string number, the first is the shader itself.
+    /// Empty for shaders not loaded from files.
+    pub files: Vec<String>,
+}
+
+impl PreprocessedSource {
+    fn from_str(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            files: vec![],
+        }
+    }
+
+    /// Replaces source string numbers in a driver info log with file names,
+    /// e.g. `1:12(5): error` from Mesa becomes `shaders/_2_lighting/lighting.glsl:12(5): error`.
+    pub fn remap_info_log(&self, info_log: &str) -> String {
+        if self.files.len() < 2 {
+            return info_log.to_string();
+        }
+        info_log
+            .lines()
+            .map(|line| self.remap_info_log_line(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn remap_info_log_line(&self, line: &str) -> String {
+        for (start, end, index, _) in source_references(line) {
+            if let Some(file) = self.files.get(index) {
+                return format!("{}{}{}", &line[..start], file, &line[end..]);
+            }
+        }
+        line.to_string()
+    }
+
+    /// File and line of the first error in a driver info log,
+    /// the file is `None` for sources not loaded from files.
+    fn locate(&self, info_log: &str) -> Option<(Option<&String>, u32)> {
+        info_log
+            .lines()
+            .find_map(|line| source_references(line).into_iter().next())
+            .map(|(_, _, index, line)| (self.files.get(index), line))
+    }
+}
+
+/// `N:LINE` (Mesa, ANGLE) and `N(LINE)` (NVIDIA) at the start of a word in an info log line,
+/// as byte range of `N`, source string number and line.
+fn source_references(line: &str) -> Vec<(usize, usize, usize, u32)> {
+    let bytes = line.as_bytes();
+    let mut references = vec![];
+    let mut start = 0;
+    while start < bytes.len() {
+        let at_word_start = start == 0 || !bytes[start - 1].is_ascii_alphanumeric();
+        let end = start
+            + bytes[start..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+        if at_word_start && end > start && matches!(bytes.get(end), Some(b':' | b'(')) {
+            let line_end = end
+                + 1
+                + bytes[end + 1..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+            if let (Ok(index), Ok(number)) = (
+                line[start..end].parse::<usize>(),
+                line[end + 1..line_end].parse::<u32>(),
+            ) {
+                references.push((start, end, index, number));
+            }
+        }
+        start = end.max(start + 1);
+    }
+    references
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ShaderType {
     Vertex,
     Fragment,
-    #[allow(dead_code)]
     Compute,
     Geometry,
+    TessControl,
+    TessEvaluation,
+}
+
+impl ShaderType {
+    fn gl_type(self) -> u32 {
+        match self {
+            ShaderType::Vertex => VERTEX_SHADER,
+            ShaderType::Fragment => FRAGMENT_SHADER,
+            ShaderType::Compute => COMPUTE_SHADER,
+            ShaderType::Geometry => GEOMETRY_SHADER,
+            ShaderType::TessControl => TESS_CONTROL_SHADER,
+            ShaderType::TessEvaluation => TESS_EVALUATION_SHADER,
+        }
+    }
+}
+
+impl fmt::Display for ShaderType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ShaderType::Vertex => "vertex",
+            ShaderType::Fragment => "fragment",
+            ShaderType::Compute => "compute",
+            ShaderType::Geometry => "geometry",
+            ShaderType::TessControl => "tessellation control",
+            ShaderType::TessEvaluation => "tessellation evaluation",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Why a shader couldn't be built, returned by `ShaderBuilder::build` and the constructors on top of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderError {
+    /// as given to `ShaderBuilder::name`
+    pub name: Option<String>,
+    /// the stage that failed to load or compile, `None` when linking failed
+    pub stage: Option<ShaderType>,
+    /// file of the first error, for stages loaded from files
+    pub file: Option<String>,
+    /// source line of the first error, when the driver reports one
+    pub line: Option<u32>,
+    /// the driver's info log, with file names in place of source string numbers
+    pub info_log: String,
+}
+
+impl ShaderError {
+    pub(crate) fn load(stage: ShaderType, path: &str, message: String) -> Self {
+        Self {
+            name: None,
+            stage: Some(stage),
+            file: Some(path.to_string()),
+            line: None,
+            info_log: message,
+        }
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.stage {
+            Some(stage) => write!(f, "Failed to compile {} shader", stage)?,
+            None => write!(f, "Failed to link shader")?,
+        }
+        if let Some(name) = &self.name {
+            write!(f, " {:?}", name)?;
+        }
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, " at {}:{}", file, line)?,
+            (Some(file), None) => write!(f, " in {}", file)?,
+            (None, Some(line)) => write!(f, " at line {}", line)?,
+            (None, None) => {}
+        }
+        write!(f, ":\n{}", self.info_log)
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+/// Collects the stages of a program with their defines, e.g.
+/// `MyShader::builder().name("scene").version(Some(ctx.suggested_shader_version()))
+/// .vertex(vs).fragment(fs).define("SHADOWS", "1").build(gl)`.
+/// Any set of stages that links works: vertex and fragment with optional geometry
+/// and tessellation stages, or a single compute stage.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderBuilder {
+    name: Option<String>,
+    version: Option<String>,
+    stages: Vec<(ShaderType, PreprocessedSource)>,
+    /// `None` for defines of every stage
+    defines: Vec<(Option<ShaderType>, String, String)>,
+}
+
+#[allow(dead_code)]
+impl ShaderBuilder {
+    /// Name for errors and the "Shader" window, like `MyShader::set_name`.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub(crate) fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub(crate) fn get_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub(crate) fn get_stage(&self, stage: ShaderType) -> Option<&PreprocessedSource> {
+        self.stages
+            .iter()
+            .find(|(s, _)| *s == stage)
+            .map(|(_, source)| source)
+    }
+
+    /// Replaces the `#version` line of every stage, e.g. with `AppContext::suggested_shader_version`.
+    pub fn version(mut self, version: Option<&str>) -> Self {
+        self.version = version.map(str::to_string);
+        self
+    }
+
+    /// Adds a stage, replacing an earlier source of the same stage.
+    pub fn stage(self, stage: ShaderType, source: &str) -> Self {
+        self.preprocessed_stage(stage, PreprocessedSource::from_str(source))
+    }
+
+    /// Adds a stage loaded with `MyShader::load_source`, errors point into its files.
+    pub fn preprocessed_stage(mut self, stage: ShaderType, source: PreprocessedSource) -> Self {
+        self.stages.retain(|(s, _)| *s != stage);
+        self.stages.push((stage, source));
+        self
+    }
+
+    pub fn vertex(self, source: &str) -> Self {
+        self.stage(ShaderType::Vertex, source)
+    }
+
+    pub fn fragment(self, source: &str) -> Self {
+        self.stage(ShaderType::Fragment, source)
+    }
+
+    pub fn geometry(self, source: &str) -> Self {
+        self.stage(ShaderType::Geometry, source)
+    }
+
+    pub fn tess_control(self, source: &str) -> Self {
+        self.stage(ShaderType::TessControl, source)
+    }
+
+    pub fn tess_evaluation(self, source: &str) -> Self {
+        self.stage(ShaderType::TessEvaluation, source)
+    }
+
+    pub fn compute(self, source: &str) -> Self {
+        self.stage(ShaderType::Compute, source)
+    }
+
+    /// `#define name value` in every stage, right after `#version`. The value may be empty.
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines
+            .push((None, name.to_string(), value.to_string()));
+        self
+    }
+
+    /// `#define name value` in `stage` only.
+    pub fn stage_define(mut self, stage: ShaderType, name: &str, value: &str) -> Self {
+        self.defines
+            .push((Some(stage), name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Compiles every stage and links them. Nothing is leaked when a stage fails.
+    pub fn build(self, gl: &SharedGl) -> Result<MyShader, ShaderError> {
+        self.check_support(gl)?;
+        let sources: Vec<(u32, String)> = self
+            .stages
+            .iter()
+            .map(|(stage, preprocessed)| (stage.gl_type(), self.stage_source(*stage, preprocessed)))
+            .collect();
+        #[cfg(not(target_arch = "wasm32"))]
+        let key = program_cache::key(gl, &sources);
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(program) = unsafe { program_cache::load(gl, key) } {
+            return Ok(self.finish(GlProgram::from_raw(gl, program)));
+        }
+
+        let mut shaders = Vec::with_capacity(sources.len());
+        for ((stage, preprocessed), (gl_type, source)) in self.stages.iter().zip(&sources) {
+            #[cfg(target_arch = "wasm32")]
+            {
+                log::info!("{}: \n{}", stage, source);
+            }
+            match MyShader::compile_shader(gl, *gl_type, source) {
+                Ok(shader) => shaders.push(shader),
+                Err(info_log) => {
+                    for shader in shaders {
+                        unsafe { gl.delete_shader(shader) };
+                    }
+                    return Err(self.compile_error(*stage, preprocessed, &info_log));
+                }
+            }
+        }
+        let program = GlProgram::new(gl).expect("Failed to create program");
+
+        unsafe {
+            for &shader in &shaders {
+                gl.attach_shader(program.raw(), shader);
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            program_cache::prepare(program.raw());
+            gl.link_program(program.raw());
+            for shader in shaders {
+                gl.detach_shader(program.raw(), shader);
+                gl.delete_shader(shader);
+            }
+        }
+
+        if !unsafe { gl.get_program_link_status(program.raw()) } {
+            let info_log = unsafe { gl.get_program_info_log(program.raw()) };
+            return Err(ShaderError {
+                name: self.name,
+                stage: None,
+                file: None,
+                line: None,
+                info_log,
+            });
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        unsafe {
+            program_cache::store(program.raw(), key)
+        };
+
+        Ok(self.finish(program))
+    }
+
+    fn finish(&self, program: GlProgram) -> MyShader {
+        let mut shader = MyShader::from_program(program);
+        if let Some(name) = &self.name {
+            shader.set_name(name);
+        }
+        shader
+    }
+
+    fn check_support(&self, gl: &Context) -> Result<(), ShaderError> {
+        let version = gl.version();
+        let context = format!(
+            "{}{}.{}",
+            if version.is_embedded { "ES " } else { "" },
+            version.major,
+            version.minor
+        );
+        for (stage, _) in &self.stages {
+            let message = match stage {
+                ShaderType::Compute if !MyShader::supports_compute(gl) => format!(
+                    "Compute shaders need OpenGL 4.3 or ES 3.1, the context is {}, \
+                     ask for 4.3 with WindowInitInfo major and minor",
+                    context
+                ),
+                ShaderType::TessControl | ShaderType::TessEvaluation
+                    if !MyShader::supports_tessellation(gl) =>
+                {
+                    format!(
+                        "Tessellation shaders need OpenGL 4.0 or ES 3.2, the context is {}, \
+                         ask for 4.0 with WindowInitInfo major and minor",
+                        context
+                    )
+                }
+                _ => continue,
+            };
+            return Err(ShaderError {
+                name: self.name.clone(),
+                stage: Some(*stage),
+                file: None,
+                line: None,
+                info_log: message,
+            });
+        }
+        Ok(())
+    }
+
+    /// The source of `stage` with the version override and its defines.
+    fn stage_source(&self, stage: ShaderType, preprocessed: &PreprocessedSource) -> String {
+        let source = MyShader::modify_shader_to_support_webgl(
+            &preprocessed.source,
+            self.version.as_deref(),
+            stage,
+        );
+        let defines: String = self
+            .defines
+            .iter()
+            .filter(|(s, _, _)| s.is_none_or(|s| s == stage))
+            .map(|(_, name, value)| format!("#define {} {}\n", name, value))
+            .collect();
+        if defines.is_empty() {
+            return source;
+        }
+        // `#line` keeps error lines pointing at the source as written
+        match source.split_once('\n') {
+            Some((version, rest)) if version.starts_with("#version") => {
+                format!("{}\n{}#line 2\n{}", version, defines, rest)
+            }
+            _ => format!("{}#line 1\n{}", defines, source),
+        }
+    }
+
+    fn compile_error(
+        &self,
+        stage: ShaderType,
+        preprocessed: &PreprocessedSource,
+        info_log: &str,
+    ) -> ShaderError {
+        let (file, line) = match preprocessed.locate(info_log) {
+            Some((file, line)) => (file.cloned(), Some(line)),
+            None => (preprocessed.files.first().cloned(), None),
+        };
+        ShaderError {
+            name: self.name.clone(),
+            stage: Some(stage),
+            file,
+            line,
+            info_log: preprocessed.remap_info_log(info_log),
+        }
+    }
 }
 
 impl MyShader {
+    /// Loads both stages through `resources::load_string`, expanding `#include`s.
     #[allow(dead_code)]
     pub async fn new(
-        gl: &Context,
+        gl: &SharedGl,
         vertex_path: &str,
         fragment_path: &str,
         shader_version: Option<&str>,
-    ) -> Result<Self, String> {
-        let vertex_shader = resources::load_string(vertex_path)
+    ) -> Result<Self, ShaderError> {
+        let vertex = Self::load_source(vertex_path)
             .await
-            .expect("Failed to load vertex shader");
-        let fragment_shader = resources::load_string(fragment_path)
+            .map_err(|e| ShaderError::load(ShaderType::Vertex, vertex_path, e))?;
+        let fragment = Self::load_source(fragment_path)
             .await
-            .expect("Failed to load fragment shader");
+            .map_err(|e| ShaderError::load(ShaderType::Fragment, fragment_path, e))?;
 
-        Self::new_from_source(gl, &vertex_shader, &fragment_shader, shader_version)
+        let mut shader = Self::from_preprocessed(gl, &vertex, &fragment, shader_version)?;
+        shader.watch_files(&vertex, &fragment, shader_version, &[]);
+        Ok(shader)
     }
 
-    pub fn new_from_source(
-        gl: &Context,
-        vertex_shader: &str,
-        fragment_shader: &str,
+    /// Reloads the shader from the files of `vertex` and `fragment` in `reload_if_changed`,
+    /// compiled with `defines` like the shader itself. Does nothing on the web.
+    #[allow(unused_variables)]
+    pub(crate) fn watch_files(
+        &mut self,
+        vertex: &PreprocessedSource,
+        fragment: &PreprocessedSource,
         shader_version: Option<&str>,
-    ) -> Result<Self, String> {
-        let vertex_shader =
-            Self::modify_shader_to_support_webgl(vertex_shader, shader_version, ShaderType::Vertex);
-        let fragment_shader = Self::modify_shader_to_support_webgl(
-            fragment_shader,
-            shader_version,
-            ShaderType::Fragment,
-        );
-
-        #[cfg(target_arch = "wasm32")]
+        defines: &[(String, String)],
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
         {
-            log::info!("vs: \n{}\n\nfs: \n{}", vertex_shader, fragment_shader);
+            self.watcher = Some(ShaderWatcher::new(
+                vertex,
+                fragment,
+                shader_version,
+                defines,
+            ));
         }
-        let program = unsafe { gl.create_program().expect("Failed to create program") };
+    }
 
-        let (vertex, fragment) = (
-            Self::compile_shader(gl, VERTEX_SHADER, &vertex_shader)?,
-            Self::compile_shader(gl, FRAGMENT_SHADER, &fragment_shader)?,
-        );
+    /// Loads `path` and everything it includes through `resources::load_string`, then expands
+    /// `#include "file"` lines, paths are relative to the including file.
+    /// Each file is included once per shader, like with `#pragma once`,
+    /// and `#line` directives keep compile errors pointing at the right file and line.
+    pub async fn load_source(path: &str) -> Result<PreprocessedSource, String> {
+        let mut sources = HashMap::new();
+        let mut pending = vec![path.to_string()];
+        while let Some(file) = pending.pop() {
+            if sources.contains_key(&file) {
+                continue;
+            }
+            let source = resources::load_string(&file)
+                .await
+                .map_err(|e| format!("Cannot load shader {}: {:?}", file, e))?;
+            pending.extend(Self::includes(&file, &source)?);
+            sources.insert(file, source);
+        }
+        Self::expand_includes(path, &sources)
+    }
 
-        unsafe {
-            gl.attach_shader(program, vertex);
-            gl.attach_shader(program, fragment);
-            gl.link_program(program);
+    /// Like `load_source`, but reads the files in the source tree, for `reload_if_changed`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_source(path: &str) -> Result<PreprocessedSource, String> {
+        let mut sources = HashMap::new();
+        let mut pending = vec![path.to_string()];
+        while let Some(file) = pending.pop() {
+            if sources.contains_key(&file) {
+                continue;
+            }
+            let source = std::fs::read_to_string(resources::source_path(&file))
+                .map_err(|e| format!("Cannot read shader {}: {:?}", file, e))?;
+            pending.extend(Self::includes(&file, &source)?);
+            sources.insert(file, source);
         }
+        Self::expand_includes(path, &sources)
+    }
 
-        if !unsafe { gl.get_program_link_status(program) } {
-            return Err(unsafe { gl.get_program_info_log(program) });
+    /// Resource paths of the files `source` includes.
+    fn includes(file: &str, source: &str) -> Result<Vec<String>, String> {
+        source
+            .lines()
+            .filter_map(|line| Self::include_directive(file, line).transpose())
+            .collect()
+    }
+
+    fn include_directive(file: &str, line: &str) -> Result<Option<String>, String> {
+        let Some(rest) = line.trim_start().strip_prefix("#include") else {
+            return Ok(None);
+        };
+        let name = rest
+            .trim()
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+            .ok_or_else(|| format!("{}: malformed include, expected #include \"file\"", file))?;
+        let mut path: Vec<&str> = file.split('/').collect();
+        path.pop();
+        for component in name.split('/') {
+            match component {
+                "." | "" => {}
+                ".." => {
+                    path.pop();
+                }
+                component => path.push(component),
+            }
         }
+        Ok(Some(path.join("/")))
+    }
 
-        unsafe {
-            gl.detach_shader(program, vertex);
-            gl.detach_shader(program, fragment);
-            gl.delete_shader(vertex);
-            gl.delete_shader(fragment);
+    /// Expands the `#include`s of `path` like `load_source`, with the files already loaded
+    /// into `sources` by resource path, e.g. embedded with `include_str!`.
+    pub fn expand_includes(
+        path: &str,
+        sources: &HashMap<String, String>,
+    ) -> Result<PreprocessedSource, String> {
+        let mut out = PreprocessedSource {
+            source: String::new(),
+            files: vec![path.to_string()],
+        };
+        let source = Self::source_of(path, sources)?;
+        if Self::includes(path, source)?.is_empty() {
+            out.source = source.clone();
+            return Ok(out);
         }
+        Self::expand_file(path, sources, &mut out)?;
+        Ok(out)
+    }
 
-        Ok(Self {
-            name: None,
-            program,
-        })
+    fn source_of<'a>(
+        file: &str,
+        sources: &'a HashMap<String, String>,
+    ) -> Result<&'a String, String> {
+        sources
+            .get(file)
+            .ok_or_else(|| format!("Cannot include {}: not loaded", file))
     }
 
-    pub fn new_with_geometry_from_source(
-        gl: &Context,
+    fn expand_file(
+        file: &str,
+        sources: &HashMap<String, String>,
+        out: &mut PreprocessedSource,
+    ) -> Result<(), String> {
+        let index = out.files.iter().position(|f| f == file).unwrap();
+        for (number, line) in Self::source_of(file, sources)?.lines().enumerate() {
+            let number = number + 1;
+            if line.starts_with("#version") {
+                if index == 0 && number == 1 {
+                    out.source.push_str(line);
+                    out.source.push('\n');
+                    out.source
+                        .push_str(&format!("#line {} {}\n", number + 1, index));
+                } else {
+                    // keep the line count, an included file may be a complete shader on its own
+                    out.source.push_str(&format!("// {}\n", line));
+                }
+                continue;
+            }
+            match Self::include_directive(file, line)? {
+                Some(included) if out.files.contains(&included) => {
+                    out.source
+                        .push_str(&format!("// {} already included\n", included));
+                }
+                Some(included) => {
+                    out.files.push(included.clone());
+                    out.source
+                        .push_str(&format!("#line 1 {}\n", out.files.len() - 1));
+                    Self::expand_file(&included, sources, out)?;
+                    out.source
+                        .push_str(&format!("#line {} {}\n", number + 1, index));
+                }
+                None => {
+                    out.source.push_str(line);
+                    out.source.push('\n');
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts a program with any set of stages, see `ShaderBuilder`.
+    pub fn builder() -> ShaderBuilder {
+        ShaderBuilder::default()
+    }
+
+    pub fn new_from_source(
+        gl: &SharedGl,
         vertex_shader: &str,
         fragment_shader: &str,
-        geometry_shader: &str,
         shader_version: Option<&str>,
-    ) -> Result<Self, String> {
-        let vertex_shader =
-            Self::modify_shader_to_support_webgl(vertex_shader, shader_version, ShaderType::Vertex);
-        let fragment_shader = Self::modify_shader_to_support_webgl(
-            fragment_shader,
-            shader_version,
-            ShaderType::Fragment,
-        );
-        let geometry_shader = Self::modify_shader_to_support_webgl(
-            geometry_shader,
-            shader_version,
-            ShaderType::Geometry,
-        );
-
-        #[cfg(target_arch = "wasm32")]
-        {
-            log::info!("vs: \n{}\n\nfs: \n{}", vertex_shader, fragment_shader);
-        }
-        let program = unsafe { gl.create_program().expect("Failed to create program") };
+    ) -> Result<Self, ShaderError> {
+        Self::builder()
+            .version(shader_version)
+            .vertex(vertex_shader)
+            .fragment(fragment_shader)
+            .build(gl)
+    }
 
-        let (vertex, fragment, geometry) = (
-            Self::compile_shader(gl, VERTEX_SHADER, &vertex_shader)?,
-            Self::compile_shader(gl, FRAGMENT_SHADER, &fragment_shader)?,
-            Self::compile_shader(gl, GEOMETRY_SHADER, &geometry_shader)?,
-        );
+    fn from_preprocessed(
+        gl: &SharedGl,
+        vertex: &PreprocessedSource,
+        fragment: &PreprocessedSource,
+        shader_version: Option<&str>,
+    ) -> Result<Self, ShaderError> {
+        Self::builder()
+            .version(shader_version)
+            .preprocessed_stage(ShaderType::Vertex, vertex.clone())
+            .preprocessed_stage(ShaderType::Fragment, fragment.clone())
+            .build(gl)
+    }
 
-        unsafe {
-            gl.attach_shader(program, vertex);
-            gl.attach_shader(program, fragment);
-            gl.attach_shader(program, geometry);
-            gl.link_program(program);
-        }
+    /// Compiles and links a compute shader. The source keeps its own `#version`, e.g. `430 core`,
+    /// unless the context is OpenGL ES. The context has to be OpenGL 4.3 (or ES 3.1), ask for it with
+    /// `WindowInitInfo.major`/`minor`. Fails on older contexts and WebGL.
+    pub fn new_compute_from_source(
+        gl: &SharedGl,
+        compute_shader: &str,
+    ) -> Result<Self, ShaderError> {
+        // the same source runs on ES 3.1, e.g. ANGLE
+        Self::builder()
+            .version(gl.version().is_embedded.then_some("#version 310 es"))
+            .compute(compute_shader)
+            .build(gl)
+    }
 
-        if !unsafe { gl.get_program_link_status(program) } {
-            return Err(unsafe { gl.get_program_info_log(program) });
-        }
+    /// Whether the context can run compute shaders, see `new_compute_from_source`.
+    pub fn supports_compute(gl: &Context) -> bool {
+        let version = gl.version();
+        let required = if version.is_embedded { (3, 1) } else { (4, 3) };
+        (version.major, version.minor) >= required
+    }
 
+    /// Runs the compute shader on `groups` work groups,
+    /// uniforms have to be set before as the program stays in use.
+    pub fn dispatch(&self, gl: &Context, groups: [u32; 3]) {
         unsafe {
-            gl.detach_shader(program, vertex);
-            gl.detach_shader(program, fragment);
-            gl.detach_shader(program, geometry);
-            gl.delete_shader(vertex);
-            gl.delete_shader(fragment);
-            gl.delete_shader(geometry);
+            gl.use_program(Some(self.program.raw()));
+            gl.dispatch_compute(groups[0], groups[1], groups[2]);
         }
+    }
 
-        Ok(Self {
-            name: None,
-            program,
-        })
+    /// Runs enough work groups of `local_size`, as declared by `layout(local_size_x = ...) in;`,
+    /// to cover `size` invocations, e.g. the pixels of an image or the particles of a buffer.
+    /// The shader has to skip invocations past the end.
+    pub fn dispatch_for(&self, gl: &Context, size: [u32; 3], local_size: [u32; 3]) {
+        assert!(
+            local_size.iter().all(|&size| size > 0),
+            "local_size {:?} has an empty dimension, GLSL defaults them to 1",
+            local_size
+        );
+        self.dispatch(
+            gl,
+            [
+                size[0].div_ceil(local_size[0]),
+                size[1].div_ceil(local_size[1]),
+                size[2].div_ceil(local_size[2]),
+            ],
+        );
     }
 
-    pub fn set_name(&mut self, name: &str) {
-        self.name = Some(name.to_string());
+    pub fn new_with_geometry_from_source(
+        gl: &SharedGl,
+        vertex_shader: &str,
+        fragment_shader: &str,
+        geometry_shader: &str,
+        shader_version: Option<&str>,
+    ) -> Result<Self, ShaderError> {
+        Self::builder()
+            .version(shader_version)
+            .vertex(vertex_shader)
+            .geometry(geometry_shader)
+            .fragment(fragment_shader)
+            .build(gl)
     }
 
-    pub fn use_shader(&self, gl: &Context) {
-        unsafe {
-            gl.use_program(Some(self.program));
+    /// Compiles and links a program with tessellation stages, drawn with `PATCHES` instead of
+    /// triangles, see `set_patch_vertices`. The control stage is optional, without it the
+    /// levels default to 1. Sources keep their own `#version`, at least `400 core`, and the
+    /// context has to be OpenGL 4.0 (or ES 3.2), ask for it with `WindowInitInfo.major`/`minor`.
+    pub fn new_with_tessellation_from_source(
+        gl: &SharedGl,
+        vertex_shader: &str,
+        tess_control_shader: Option<&str>,
+        tess_evaluation_shader: &str,
+        geometry_shader: Option<&str>,
+        fragment_shader: &str,
+    ) -> Result<Self, ShaderError> {
+        let mut builder = Self::builder()
+            .version(gl.version().is_embedded.then_some("#version 320 es"))
+            .vertex(vertex_shader)
+            .tess_evaluation(tess_evaluation_shader)
+            .fragment(fragment_shader);
+        if let Some(tess_control_shader) = tess_control_shader {
+            builder = builder.tess_control(tess_control_shader);
+        }
+        if let Some(geometry_shader) = geometry_shader {
+            builder = builder.geometry(geometry_shader);
         }
+        builder.build(gl)
     }
 
-    #[allow(dead_code)]
-    pub fn set_bool(&self, gl: &Context, name: &str, value: bool) {
-        unsafe {
-            let location = gl
-                .get_uniform_location(self.program, name)
-                .unwrap_or_else(|| panic!("Cannot find uniform location {} {:?}", name, self.name));
-            gl.uniform_1_i32(Some(&location), value as i32);
-        }
+    /// Whether the context can run tessellation shaders, see `new_with_tessellation_from_source`.
+    pub fn supports_tessellation(gl: &Context) -> bool {
+        let version = gl.version();
+        let required = if version.is_embedded { (3, 2) } else { (4, 0) };
+        (version.major, version.minor) >= required
     }
 
-    pub fn set_int(&self, gl: &Context, name: &str, value: i32) {
+    /// Sets how many vertices make up a patch for the following `PATCHES` draws,
+    /// e.g. 4 for quads. It has to match `layout(vertices = N) out` of the control stage.
+    pub fn set_patch_vertices(gl: &Context, count: i32) {
         unsafe {
-            let location = gl
-                .get_uniform_location(self.program, name)
-                .unwrap_or_else(|| panic!("Cannot find uniform location {} {:?}", name, self.name));
-            gl.uniform_1_i32(Some(&location), value);
+            gl.patch_parameter_i32(PATCH_VERTICES, count);
         }
     }
 
-    pub fn try_set_int(&self, gl: &Context, name: &str, value: i32) -> bool {
-        unsafe {
-            let location = gl.get_uniform_location(self.program, name);
-            if let Some(location) = location {
-                gl.uniform_1_i32(Some(&location), value);
-                return true;
-            }
+    fn from_program(program: GlProgram) -> Self {
+        shader_reflection::track(program.raw());
+        Self {
+            name: None,
+            uniforms: unsafe { query_uniforms(program.gl(), program.raw()) },
+            dropped_uniforms: HashSet::new(),
+            program,
+            #[cfg(not(target_arch = "wasm32"))]
+            watcher: None,
         }
-        false
     }
 
-    pub fn set_float(&self, gl: &Context, name: &str, value: f32) {
+    pub fn set_name(&mut self, name: &str) {
+        shader_reflection::set_name(self.program.raw(), name);
+        self.name = Some(name.to_string());
+    }
+
+    pub fn use_shader(&self, gl: &Context) {
         unsafe {
-            let location = gl
-                .get_uniform_location(self.program, name)
-                .unwrap_or_else(|| panic!("Cannot find uniform location {} {:?}", name, self.name));
-            gl.uniform_1_f32(Some(&location), value);
+            gl.use_program(Some(self.program.raw()));
         }
     }
 
-    pub fn try_set_float(&self, gl: &Context, name: &str, value: f32) -> bool {
-        unsafe {
-            let location = gl.get_uniform_location(self.program, name);
-            if let Some(location) = location {
-                gl.uniform_1_f32(Some(&location), value);
-                return true;
+    /// Looks up `name` in the uniforms queried after linking.
+    /// Panics when it isn't an active uniform, however the shader was created, unless it was one
+    /// before a reload: an edit may leave a uniform unused and the compiler drops it.
+    /// Panics as well when the uniform's GLSL type doesn't match `kind`
+    /// or it has fewer than `count` array elements left.
+    fn uniform_location(
+        &self,
+        name: &str,
+        kind: UniformKind,
+        count: i32,
+    ) -> Option<&UniformLocation> {
+        let Some(uniform) = self.uniforms.get(name) else {
+            if self.dropped_uniforms.contains(name) {
+                log::trace!("Skipping inactive uniform {} {:?}", name, self.name);
+                shader_reflection::record_missing(self.program.raw(), name);
+                return None;
             }
+            panic!("Cannot find uniform location {} {:?}", name, self.name);
+        };
+        match self.checked_location(name, uniform, kind, count) {
+            Ok(location) => Some(location),
+            Err(message) => panic!("{}", message),
         }
-        false
     }
 
-    pub fn set_mat4(&self, gl: &Context, name: &str, value: &nalgebra_glm::Mat4) {
-        unsafe {
-            let location = gl
-                .get_uniform_location(self.program, name)
-                .unwrap_or_else(|| panic!("Cannot find uniform location {} {:?}", name, self.name));
-            gl.uniform_matrix_4_f32_slice(Some(&location), false, value.as_slice());
+    /// Like `uniform_location`, but a missing uniform is `None` instead of a panic,
+    /// and so is a mismatched one, with a warning.
+    fn try_uniform_location(
+        &self,
+        name: &str,
+        kind: UniformKind,
+        count: i32,
+    ) -> Option<&UniformLocation> {
+        let Some(uniform) = self.uniforms.get(name) else {
+            shader_reflection::record_missing(self.program.raw(), name);
+            return None;
+        };
+        match self.checked_location(name, uniform, kind, count) {
+            Ok(location) => Some(location),
+            Err(message) => {
+                log::warn!("{}", message);
+                None
+            }
         }
     }
 
-    pub fn set_vec3(&self, gl: &Context, name: &str, value: &nalgebra_glm::Vec3) {
-        unsafe {
-            let location = gl
-                .get_uniform_location(self.program, name)
-                .unwrap_or_else(|| panic!("Cannot find uniform location {} {:?}", name, self.name));
-            gl.uniform_3_f32(Some(&location), value.x, value.y, value.z);
+    /// The location of `uniform`, or why it can't be set as `count` values of `kind`.
+    fn checked_location<'a>(
+        &self,
+        name: &str,
+        uniform: &'a UniformInfo,
+        kind: UniformKind,
+        count: i32,
+    ) -> Result<&'a UniformLocation, String> {
+        if !kind.accepts(uniform.utype) {
+            return Err(format!(
+                "Cannot set uniform {} {:?} of type {} as {:?}",
+                name,
+                self.name,
+                type_name(uniform.utype),
+                kind
+            ));
         }
+        if count > uniform.size {
+            return Err(format!(
+                "Cannot set {} values to uniform {} {:?}, it has {} elements left",
+                count, name, self.name, uniform.size
+            ));
+        }
+        Ok(&uniform.location)
+    }
+
+    uniform_setters! {
+        set_bool, try_set_bool(value: bool) as Bool, |gl, location| {
+            gl.uniform_1_i32(location, value as i32)
+        };
+        set_int, try_set_int(value: i32) as Int, |gl, location| {
+            gl.uniform_1_i32(location, value)
+        };
+        set_uint, try_set_uint(value: u32) as Uint, |gl, location| {
+            gl.uniform_1_u32(location, value)
+        };
+        set_float, try_set_float(value: f32) as Float, |gl, location| {
+            gl.uniform_1_f32(location, value)
+        };
+        set_vec2, try_set_vec2(value: &glm::Vec2) as Vec2, |gl, location| {
+            gl.uniform_2_f32(location, value.x, value.y)
+        };
+        set_vec3, try_set_vec3(value: &glm::Vec3) as Vec3, |gl, location| {
+            gl.uniform_3_f32(location, value.x, value.y, value.z)
+        };
+        set_vec4, try_set_vec4(value: &glm::Vec4) as Vec4, |gl, location| {
+            gl.uniform_4_f32(location, value.x, value.y, value.z, value.w)
+        };
+        set_ivec2, try_set_ivec2(value: &glm::IVec2) as IVec2, |gl, location| {
+            gl.uniform_2_i32(location, value.x, value.y)
+        };
+        set_ivec3, try_set_ivec3(value: &glm::IVec3) as IVec3, |gl, location| {
+            gl.uniform_3_i32(location, value.x, value.y, value.z)
+        };
+        set_ivec4, try_set_ivec4(value: &glm::IVec4) as IVec4, |gl, location| {
+            gl.uniform_4_i32(location, value.x, value.y, value.z, value.w)
+        };
+        set_mat3, try_set_mat3(value: &glm::Mat3) as Mat3, |gl, location| {
+            gl.uniform_matrix_3_f32_slice(location, false, value.as_slice())
+        };
+        set_mat4, try_set_mat4(value: &glm::Mat4) as Mat4, |gl, location| {
+            gl.uniform_matrix_4_f32_slice(location, false, value.as_slice())
+        };
+        set_bool_array, try_set_bool_array(values: &[bool]) as Bool * values.len(),
+        |gl, location| {
+            let values: Vec<i32> = values.iter().map(|value| *value as i32).collect();
+            gl.uniform_1_i32_slice(location, &values)
+        };
+        set_int_array, try_set_int_array(values: &[i32]) as Int * values.len(),
+        |gl, location| {
+            gl.uniform_1_i32_slice(location, values)
+        };
+        set_uint_array, try_set_uint_array(values: &[u32]) as Uint * values.len(),
+        |gl, location| {
+            gl.uniform_1_u32_slice(location, values)
+        };
+        set_float_array, try_set_float_array(values: &[f32]) as Float * values.len(),
+        |gl, location| {
+            gl.uniform_1_f32_slice(location, values)
+        };
+        set_vec2_array, try_set_vec2_array(values: &[glm::Vec2]) as Vec2 * values.len(),
+        |gl, location| {
+            gl.uniform_2_f32_slice(location, &flatten(values))
+        };
+        set_vec3_array, try_set_vec3_array(values: &[glm::Vec3]) as Vec3 * values.len(),
+        |gl, location| {
+            gl.uniform_3_f32_slice(location, &flatten(values))
+        };
+        set_vec4_array, try_set_vec4_array(values: &[glm::Vec4]) as Vec4 * values.len(),
+        |gl, location| {
+            gl.uniform_4_f32_slice(location, &flatten(values))
+        };
+        set_ivec2_array, try_set_ivec2_array(values: &[glm::IVec2]) as IVec2 * values.len(),
+        |gl, location| {
+            gl.uniform_2_i32_slice(location, &flatten(values))
+        };
+        set_ivec3_array, try_set_ivec3_array(values: &[glm::IVec3]) as IVec3 * values.len(),
+        |gl, location| {
+            gl.uniform_3_i32_slice(location, &flatten(values))
+        };
+        set_ivec4_array, try_set_ivec4_array(values: &[glm::IVec4]) as IVec4 * values.len(),
+        |gl, location| {
+            gl.uniform_4_i32_slice(location, &flatten(values))
+        };
+        set_mat3_array, try_set_mat3_array(values: &[glm::Mat3]) as Mat3 * values.len(),
+        |gl, location| {
+            gl.uniform_matrix_3_f32_slice(location, false, &flatten(values))
+        };
+        set_mat4_array, try_set_mat4_array(values: &[glm::Mat4]) as Mat4 * values.len(),
+        |gl, location| {
+            gl.uniform_matrix_4_f32_slice(location, false, &flatten(values))
+        };
     }
 
     pub fn compile_shader(
@@ -229,16 +1083,93 @@ impl MyShader {
         }
 
         if !unsafe { gl.get_shader_compile_status(shader) } {
-            return Err(unsafe { gl.get_shader_info_log(shader) });
+            let info_log = unsafe { gl.get_shader_info_log(shader) };
+            unsafe { gl.delete_shader(shader) };
+            return Err(info_log);
         }
 
         Ok(shader)
     }
 
-    pub fn delete(&self, gl: &Context) {
-        unsafe {
-            gl.delete_program(self.program);
+    /// Recompiles and relinks from the files given to `new` when one of them changed on disk,
+    /// carrying over the values of plain uniforms, e.g. sampler units set once at startup.
+    /// On error the old program is kept and the info log is logged.
+    /// Returns whether the program was replaced, always false on the web.
+    #[allow(dead_code)]
+    pub fn reload_if_changed(&mut self, gl: &SharedGl) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(watcher) = self.watcher.as_mut() else {
+                return false;
+            };
+            let modified = watcher.last_modified();
+            if modified <= watcher.modified {
+                return false;
+            }
+            watcher.modified = modified;
+
+            let sources = Self::read_source(&watcher.vertex_path)
+                .and_then(|vertex| Ok((vertex, Self::read_source(&watcher.fragment_path)?)));
+            let (vertex, fragment) = match sources {
+                Ok(sources) => sources,
+                Err(e) => {
+                    log::warn!("Cannot reload shader {:?}: {}", self.name, e);
+                    return false;
+                }
+            };
+            // includes may have been added or removed
+            watcher.watch(&vertex, &fragment);
+            let builder = watcher.defines.iter().fold(
+                Self::builder().version(watcher.shader_version.as_deref()),
+                |builder, (name, value)| builder.define(name, value),
+            );
+            match builder
+                .preprocessed_stage(ShaderType::Vertex, vertex)
+                .preprocessed_stage(ShaderType::Fragment, fragment)
+                .build(gl)
+            {
+                Ok(mut shader) => unsafe {
+                    copy_uniforms(gl, self.program.raw(), shader.program.raw());
+                    shader_reflection::replace(self.program.raw(), shader.program.raw());
+                    // the old program is deleted with `shader`
+                    std::mem::swap(&mut self.program, &mut shader.program);
+                    self.dropped_uniforms
+                        .extend(self.uniforms.drain().map(|(name, _)| name));
+                    self.uniforms = std::mem::take(&mut shader.uniforms);
+                    let uniforms = &self.uniforms;
+                    self.dropped_uniforms
+                        .retain(|name| !uniforms.contains_key(name));
+                    log::info!(
+                        "Reloaded shader {:?} from {:?} and {:?}",
+                        self.name,
+                        watcher.vertex_path,
+                        watcher.fragment_path
+                    );
+                    true
+                },
+                Err(error) => {
+                    log::error!(
+                        "Failed to reload shader {:?} from {:?} and {:?}, keeping the old program: {}",
+                        self.name,
+                        watcher.vertex_path,
+                        watcher.fragment_path,
+                        error
+                    );
+                    false
+                }
+            }
         }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = gl;
+            false
+        }
+    }
+
+    /// Active attributes, uniforms and uniform blocks of the program,
+    /// the same as the "Shader" window shows.
+    pub fn reflect(&self, gl: &Context) -> ShaderReflection {
+        unsafe { shader_reflection::reflect_program(gl, self.program.raw()) }
     }
 
     /// for better compatibility with WebGL
@@ -265,7 +1196,11 @@ impl MyShader {
                 let mut lines = shader_source.lines();
                 let first_line = lines.next().unwrap();
                 let rest = lines.collect::<Vec<&str>>().join("\n");
-                format!("{}\nprecision mediump float;\n{}", first_line, rest)
+                // `#line` keeps error lines pointing at the source as written
+                format!(
+                    "{}\nprecision mediump float;\n#line 2\n{}",
+                    first_line, rest
+                )
             } else {
                 shader_source
             };
@@ -274,6 +1209,190 @@ impl MyShader {
     }
 
     pub fn program(&self) -> Program {
-        self.program
+        self.program.raw()
+    }
+}
+
+impl Drop for MyShader {
+    fn drop(&mut self) {
+        shader_reflection::untrack(self.program.raw());
+    }
+}
+
+/// Active uniforms of `program` by name, array elements included.
+/// Members of uniform blocks have no location and are left out.
+unsafe fn query_uniforms(gl: &Context, program: Program) -> HashMap<String, UniformInfo> {
+    let mut uniforms = HashMap::new();
+    for index in 0..gl.get_active_uniforms(program) {
+        let Some(uniform) = gl.get_active_uniform(program, index) else {
+            continue;
+        };
+        let Some(base) = uniform.name.strip_suffix("[0]") else {
+            if let Some(location) = gl.get_uniform_location(program, &uniform.name) {
+                let info = UniformInfo {
+                    location,
+                    utype: uniform.utype,
+                    size: uniform.size,
+                };
+                uniforms.insert(uniform.name, info);
+            }
+            continue;
+        };
+        for element in 0..uniform.size {
+            let name = format!("{}[{}]", base, element);
+            let Some(location) = gl.get_uniform_location(program, &name) else {
+                continue;
+            };
+            let info = UniformInfo {
+                location,
+                utype: uniform.utype,
+                size: uniform.size - element,
+            };
+            if element == 0 {
+                uniforms.insert(base.to_string(), info.clone());
+            }
+            uniforms.insert(name, info);
+        }
+    }
+    uniforms
+}
+
+/// Components of vectors or matrices one after the other, as the glUniform*v functions take them.
+fn flatten<T: glm::Scalar + Copy, const R: usize, const C: usize>(
+    values: &[glm::TMat<T, R, C>],
+) -> Vec<T> {
+    values.iter().flat_map(|v| v.iter().copied()).collect()
+}
+
+fn is_sampler(utype: u32) -> bool {
+    matches!(
+        utype,
+        glow::SAMPLER_2D
+            | glow::SAMPLER_3D
+            | glow::SAMPLER_CUBE
+            | glow::SAMPLER_2D_SHADOW
+            | glow::SAMPLER_2D_ARRAY
+            | glow::SAMPLER_2D_ARRAY_SHADOW
+            | glow::SAMPLER_CUBE_SHADOW
+            | glow::SAMPLER_2D_MULTISAMPLE
+            | glow::INT_SAMPLER_2D
+            | glow::INT_SAMPLER_3D
+            | glow::INT_SAMPLER_CUBE
+            | glow::INT_SAMPLER_2D_ARRAY
+            | glow::UNSIGNED_INT_SAMPLER_2D
+            | glow::UNSIGNED_INT_SAMPLER_3D
+            | glow::UNSIGNED_INT_SAMPLER_CUBE
+            | glow::UNSIGNED_INT_SAMPLER_2D_ARRAY
+    )
+}
+
+/// GLSL name of a uniform or attribute type for messages.
+pub(crate) fn type_name(utype: u32) -> String {
+    let name = match utype {
+        glow::FLOAT => "float",
+        glow::FLOAT_VEC2 => "vec2",
+        glow::FLOAT_VEC3 => "vec3",
+        glow::FLOAT_VEC4 => "vec4",
+        glow::FLOAT_MAT2 => "mat2",
+        glow::FLOAT_MAT3 => "mat3",
+        glow::FLOAT_MAT4 => "mat4",
+        glow::INT => "int",
+        glow::INT_VEC2 => "ivec2",
+        glow::INT_VEC3 => "ivec3",
+        glow::INT_VEC4 => "ivec4",
+        glow::UNSIGNED_INT => "uint",
+        glow::UNSIGNED_INT_VEC2 => "uvec2",
+        glow::UNSIGNED_INT_VEC3 => "uvec3",
+        glow::UNSIGNED_INT_VEC4 => "uvec4",
+        glow::BOOL => "bool",
+        glow::BOOL_VEC2 => "bvec2",
+        glow::BOOL_VEC3 => "bvec3",
+        glow::BOOL_VEC4 => "bvec4",
+        glow::SAMPLER_2D => "sampler2D",
+        glow::SAMPLER_CUBE => "samplerCube",
+        glow::SAMPLER_2D_SHADOW => "sampler2DShadow",
+        utype if is_sampler(utype) => "sampler",
+        _ => return format!("{:#06x}", utype),
+    };
+    name.to_string()
+}
+
+/// Sets every active uniform of `to` to its value in `from`, matched by name.
+/// Uniform block members and block bindings are left alone.
+#[cfg(not(target_arch = "wasm32"))]
+unsafe fn copy_uniforms(gl: &Context, from: Program, to: Program) {
+    gl.use_program(Some(to));
+    for index in 0..gl.get_active_uniforms(from) {
+        let Some(uniform) = gl.get_active_uniform(from, index) else {
+            continue;
+        };
+        let base = uniform.name.trim_end_matches("[0]");
+        for element in 0..uniform.size {
+            let name = if uniform.size > 1 {
+                format!("{}[{}]", base, element)
+            } else {
+                uniform.name.clone()
+            };
+            let (Some(src), Some(dst)) = (
+                gl.get_uniform_location(from, &name),
+                gl.get_uniform_location(to, &name),
+            ) else {
+                continue;
+            };
+            let dst = Some(&dst);
+            match uniform.utype {
+                glow::FLOAT
+                | glow::FLOAT_VEC2
+                | glow::FLOAT_VEC3
+                | glow::FLOAT_VEC4
+                | glow::FLOAT_MAT2
+                | glow::FLOAT_MAT3
+                | glow::FLOAT_MAT4 => {
+                    let mut v = [0.0; 16];
+                    gl.get_uniform_f32(from, &src, &mut v);
+                    match uniform.utype {
+                        glow::FLOAT => gl.uniform_1_f32_slice(dst, &v[..1]),
+                        glow::FLOAT_VEC2 => gl.uniform_2_f32_slice(dst, &v[..2]),
+                        glow::FLOAT_VEC3 => gl.uniform_3_f32_slice(dst, &v[..3]),
+                        glow::FLOAT_VEC4 => gl.uniform_4_f32_slice(dst, &v[..4]),
+                        glow::FLOAT_MAT2 => gl.uniform_matrix_2_f32_slice(dst, false, &v[..4]),
+                        glow::FLOAT_MAT3 => gl.uniform_matrix_3_f32_slice(dst, false, &v[..9]),
+                        _ => gl.uniform_matrix_4_f32_slice(dst, false, &v),
+                    }
+                }
+                glow::INT_VEC2 | glow::BOOL_VEC2 => {
+                    let mut v = [0; 2];
+                    gl.get_uniform_i32(from, &src, &mut v);
+                    gl.uniform_2_i32_slice(dst, &v);
+                }
+                glow::INT_VEC3 | glow::BOOL_VEC3 => {
+                    let mut v = [0; 3];
+                    gl.get_uniform_i32(from, &src, &mut v);
+                    gl.uniform_3_i32_slice(dst, &v);
+                }
+                glow::INT_VEC4 | glow::BOOL_VEC4 => {
+                    let mut v = [0; 4];
+                    gl.get_uniform_i32(from, &src, &mut v);
+                    gl.uniform_4_i32_slice(dst, &v);
+                }
+                glow::INT
+                | glow::BOOL
+                | glow::SAMPLER_2D
+                | glow::SAMPLER_3D
+                | glow::SAMPLER_CUBE
+                | glow::SAMPLER_2D_SHADOW
+                | glow::SAMPLER_2D_ARRAY
+                | glow::SAMPLER_2D_MULTISAMPLE => {
+                    let mut v = [0; 1];
+                    gl.get_uniform_i32(from, &src, &mut v);
+                    gl.uniform_1_i32_slice(dst, &v);
+                }
+                _ => log::debug!(
+                    "Not carrying over uniform {} of type {}",
+                    name,
+                    uniform.utype
+                ),
+            }
+        }
     }
 }
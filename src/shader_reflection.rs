@@ -0,0 +1,292 @@
+use crate::shader::type_name;
+use glow::{Context, HasContext, Program};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+/// What the linker kept of a program, see `MyShader::reflect`.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderReflection {
+    pub attributes: Vec<ActiveAttribute>,
+    /// uniforms set with `set_*`, block members are listed with their block
+    pub uniforms: Vec<ActiveUniform>,
+    pub blocks: Vec<ActiveBlock>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveAttribute {
+    pub name: String,
+    pub utype: u32,
+    pub size: i32,
+    pub location: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveUniform {
+    pub name: String,
+    pub utype: u32,
+    /// array length, 1 for plain uniforms
+    pub size: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveBlock {
+    pub name: String,
+    pub binding: i32,
+    /// bytes the buffer bound to it needs
+    pub data_size: i32,
+    pub members: Vec<ActiveUniform>,
+}
+
+impl ActiveAttribute {
+    pub fn type_name(&self) -> String {
+        type_name(self.utype)
+    }
+}
+
+impl ActiveUniform {
+    pub fn type_name(&self) -> String {
+        type_name(self.utype)
+    }
+}
+
+pub(crate) unsafe fn reflect_program(gl: &Context, program: Program) -> ShaderReflection {
+    let attributes = (0..gl.get_active_attributes(program))
+        .filter_map(|index| gl.get_active_attribute(program, index))
+        .map(|attribute| ActiveAttribute {
+            location: gl.get_attrib_location(program, &attribute.name),
+            name: attribute.name,
+            utype: attribute.atype,
+            size: attribute.size,
+        })
+        .collect();
+
+    let mut uniforms = vec![];
+    // block members are the uniforms without a location
+    let mut block_members = 0;
+    for index in 0..gl.get_active_uniforms(program) {
+        let Some(uniform) = gl.get_active_uniform(program, index) else {
+            continue;
+        };
+        if gl.get_uniform_location(program, &uniform.name).is_none() {
+            block_members += 1;
+            continue;
+        }
+        uniforms.push(ActiveUniform {
+            name: uniform.name,
+            utype: uniform.utype,
+            size: uniform.size,
+        });
+    }
+
+    let mut blocks = vec![];
+    for index in 0..active_uniform_blocks(gl, program, block_members) {
+        let count = gl.get_active_uniform_block_parameter_i32(
+            program,
+            index,
+            glow::UNIFORM_BLOCK_ACTIVE_UNIFORMS,
+        );
+        let mut member_indices = vec![0; count.max(0) as usize];
+        gl.get_active_uniform_block_parameter_i32_slice(
+            program,
+            index,
+            glow::UNIFORM_BLOCK_ACTIVE_UNIFORM_INDICES,
+            &mut member_indices,
+        );
+        let members = member_indices
+            .into_iter()
+            .filter_map(|member| gl.get_active_uniform(program, member as u32))
+            .map(|uniform| ActiveUniform {
+                name: uniform.name,
+                utype: uniform.utype,
+                size: uniform.size,
+            })
+            .collect();
+        blocks.push(ActiveBlock {
+            name: gl.get_active_uniform_block_name(program, index),
+            binding: gl.get_active_uniform_block_parameter_i32(
+                program,
+                index,
+                glow::UNIFORM_BLOCK_BINDING,
+            ),
+            data_size: gl.get_active_uniform_block_parameter_i32(
+                program,
+                index,
+                glow::UNIFORM_BLOCK_DATA_SIZE,
+            ),
+            members,
+        });
+    }
+
+    ShaderReflection {
+        attributes,
+        uniforms,
+        blocks,
+    }
+}
+
+/// `GL_ACTIVE_UNIFORM_BLOCKS` of the program, glow 0.13 has no getter for it.
+#[cfg(not(target_arch = "wasm32"))]
+unsafe fn active_uniform_blocks(_gl: &Context, program: Program, _block_members: u32) -> u32 {
+    crate::program_cache::program_parameter_i32(program, glow::ACTIVE_UNIFORM_BLOCKS)
+        .unwrap_or(0)
+        .max(0) as u32
+}
+
+/// WebGL's `getProgramParameter` isn't reachable through glow, but WebGL has no atomic counters,
+/// so every uniform without a location is a block member and the blocks are the first indices
+/// whose members add up to them.
+#[cfg(target_arch = "wasm32")]
+unsafe fn active_uniform_blocks(gl: &Context, program: Program, block_members: u32) -> u32 {
+    let (mut blocks, mut found) = (0, 0);
+    while found < block_members {
+        let count = gl.get_active_uniform_block_parameter_i32(
+            program,
+            blocks,
+            glow::UNIFORM_BLOCK_ACTIVE_UNIFORMS,
+        );
+        if count <= 0 {
+            break;
+        }
+        found += count as u32;
+        blocks += 1;
+    }
+    blocks
+}
+
+/// A program of a `MyShader` that hasn't been deleted yet, for the "Shader" window.
+struct LiveShader {
+    program: Program,
+    name: Option<String>,
+    /// uniforms set by name that the program doesn't have, most likely optimised out
+    missing: BTreeSet<String>,
+}
+
+thread_local! {
+    static LIVE_SHADERS: RefCell<Vec<LiveShader>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(crate) fn track(program: Program) {
+    LIVE_SHADERS.with_borrow_mut(|shaders| {
+        shaders.push(LiveShader {
+            program,
+            name: None,
+            missing: BTreeSet::new(),
+        })
+    });
+}
+
+pub(crate) fn untrack(program: Program) {
+    LIVE_SHADERS.with_borrow_mut(|shaders| shaders.retain(|shader| shader.program != program));
+}
+
+/// Keeps name and missing uniforms when a reloaded program replaces the old one.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn replace(old: Program, new: Program) {
+    LIVE_SHADERS.with_borrow_mut(|shaders| {
+        shaders.retain(|shader| shader.program != new);
+        if let Some(shader) = shaders.iter_mut().find(|shader| shader.program == old) {
+            shader.program = new;
+        }
+    });
+}
+
+pub(crate) fn set_name(program: Program, name: &str) {
+    LIVE_SHADERS.with_borrow_mut(|shaders| {
+        if let Some(shader) = shaders.iter_mut().find(|shader| shader.program == program) {
+            shader.name = Some(name.to_string());
+        }
+    });
+}
+
+pub(crate) fn record_missing(program: Program, uniform: &str) {
+    LIVE_SHADERS.with_borrow_mut(|shaders| {
+        if let Some(shader) = shaders.iter_mut().find(|shader| shader.program == program) {
+            if !shader.missing.contains(uniform) {
+                shader.missing.insert(uniform.to_string());
+            }
+        }
+    });
+}
+
+/// Lists every live shader with what `MyShader::reflect` finds, opened from the default
+/// `Application::ui`. Uniforms that were set but aren't active show up as well.
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+pub fn shader_window(gl: &Context, egui_ctx: &egui::Context) {
+    egui::Window::new("Shader")
+        .default_open(false)
+        .vscroll(true)
+        .show(egui_ctx, |ui| {
+            LIVE_SHADERS.with_borrow(|shaders| {
+                if shaders.is_empty() {
+                    ui.label("No shaders");
+                }
+                for shader in shaders {
+                    let title = match &shader.name {
+                        Some(name) => name.clone(),
+                        None => format!("Program {:?}", shader.program),
+                    };
+                    egui::CollapsingHeader::new(title)
+                        .id_source(shader.program)
+                        .show(ui, |ui| {
+                            let reflection = unsafe { reflect_program(gl, shader.program) };
+                            reflection_ui(ui, &reflection, &shader.missing);
+                        });
+                }
+            });
+        });
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+fn reflection_ui(ui: &mut egui::Ui, reflection: &ShaderReflection, missing: &BTreeSet<String>) {
+    fn array_suffix(size: i32) -> String {
+        if size > 1 {
+            format!("[{}]", size)
+        } else {
+            String::new()
+        }
+    }
+
+    ui.strong("Attributes");
+    for attribute in &reflection.attributes {
+        let location = attribute
+            .location
+            .map_or("-".to_string(), |location| location.to_string());
+        ui.label(format!(
+            "{}: {} {}{}",
+            location,
+            attribute.type_name(),
+            attribute.name,
+            array_suffix(attribute.size)
+        ));
+    }
+    ui.strong("Uniforms");
+    for uniform in &reflection.uniforms {
+        ui.label(format!(
+            "{} {}{}",
+            uniform.type_name(),
+            uniform.name,
+            array_suffix(uniform.size)
+        ));
+    }
+    for block in &reflection.blocks {
+        ui.strong(format!(
+            "Block {} (binding {}, {} bytes)",
+            block.name, block.binding, block.data_size
+        ));
+        for member in &block.members {
+            ui.label(format!(
+                "{} {}{}",
+                member.type_name(),
+                member.name,
+                array_suffix(member.size)
+            ));
+        }
+    }
+    if !missing.is_empty() {
+        ui.strong("Set but not active");
+        for name in missing {
+            ui.colored_label(egui::Color32::YELLOW, name)
+                .on_hover_text("not in the program, unused uniforms are optimised out");
+        }
+    }
+}
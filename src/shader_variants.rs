@@ -0,0 +1,127 @@
+use crate::shader::{MyShader, ShaderBuilder, ShaderError, ShaderType};
+use crate::window::SharedGl;
+use std::collections::HashMap;
+
+/// One shader compiled into variants by the defines that are set, e.g. `SHADOWS`, `PCF` or `GAMMA`,
+/// for sources that switch features with `#ifdef`. A variant is compiled the first time it's asked
+/// for and cached by its set of defines, each defined as `1`.
+pub struct ShaderVariants {
+    builder: ShaderBuilder,
+    /// whether the stages were loaded from files and variants reload when they change
+    watched: bool,
+    /// by sorted define names
+    variants: HashMap<Vec<String>, MyShader>,
+}
+
+#[allow(dead_code)]
+impl ShaderVariants {
+    /// Variants of the stages of `builder`, defines given to it are set in every variant.
+    pub fn new(builder: ShaderBuilder) -> Self {
+        Self {
+            builder,
+            watched: false,
+            variants: HashMap::new(),
+        }
+    }
+
+    /// Like `MyShader::new`, loads both stages through `resources::load_string`, expanding `#include`s.
+    /// Variants reload on desktop when the files change, see `reload_if_changed`.
+    pub async fn load(
+        vertex_path: &str,
+        fragment_path: &str,
+        shader_version: Option<&str>,
+    ) -> Result<Self, ShaderError> {
+        let vertex = MyShader::load_source(vertex_path)
+            .await
+            .map_err(|e| ShaderError::load(ShaderType::Vertex, vertex_path, e))?;
+        let fragment = MyShader::load_source(fragment_path)
+            .await
+            .map_err(|e| ShaderError::load(ShaderType::Fragment, fragment_path, e))?;
+        let builder = MyShader::builder()
+            .version(shader_version)
+            .preprocessed_stage(ShaderType::Vertex, vertex)
+            .preprocessed_stage(ShaderType::Fragment, fragment);
+        Ok(Self {
+            builder,
+            watched: true,
+            variants: HashMap::new(),
+        })
+    }
+
+    /// The variant with exactly `defines` set, compiled now if it's the first time.
+    /// The order of `defines` doesn't matter.
+    pub fn variant(&mut self, gl: &SharedGl, defines: &[&str]) -> Result<&MyShader, ShaderError> {
+        let key = Self::key(defines);
+        if !self.variants.contains_key(&key) {
+            let shader = self.compile(gl, &key)?;
+            self.variants.insert(key.clone(), shader);
+        }
+        Ok(&self.variants[&key])
+    }
+
+    /// The variant with `defines` set if it was compiled before.
+    pub fn get(&self, defines: &[&str]) -> Option<&MyShader> {
+        self.variants.get(&Self::key(defines))
+    }
+
+    /// Compiles variants ahead of drawing, to not stall the first frame that needs them.
+    pub fn compile_all(&mut self, gl: &SharedGl, variants: &[&[&str]]) -> Result<(), ShaderError> {
+        for defines in variants {
+            self.variant(gl, defines)?;
+        }
+        Ok(())
+    }
+
+    /// Calls `MyShader::reload_if_changed` on every compiled variant,
+    /// returns whether any of them was reloaded.
+    pub fn reload_if_changed(&mut self, gl: &SharedGl) -> bool {
+        let mut reloaded = false;
+        for shader in self.variants.values_mut() {
+            reloaded |= shader.reload_if_changed(gl);
+        }
+        reloaded
+    }
+
+    pub fn len(&self) -> usize {
+        self.variants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    fn key(defines: &[&str]) -> Vec<String> {
+        let mut key: Vec<String> = defines.iter().map(|define| define.to_string()).collect();
+        key.sort();
+        key.dedup();
+        key
+    }
+
+    fn compile(&self, gl: &SharedGl, key: &[String]) -> Result<MyShader, ShaderError> {
+        let mut builder = self.builder.clone();
+        // so variants can be told apart in errors and the "Shader" window
+        let name = match self.builder.get_name() {
+            Some(name) if key.is_empty() => name.to_string(),
+            Some(name) => format!("{} [{}]", name, key.join(", ")),
+            None => format!("[{}]", key.join(", ")),
+        };
+        builder = builder.name(&name);
+        for define in key {
+            builder = builder.define(define, "1");
+        }
+        let mut shader = builder.build(gl)?;
+        if self.watched {
+            if let (Some(vertex), Some(fragment)) = (
+                self.builder.get_stage(ShaderType::Vertex),
+                self.builder.get_stage(ShaderType::Fragment),
+            ) {
+                let defines: Vec<(String, String)> = key
+                    .iter()
+                    .map(|define| (define.clone(), "1".to_string()))
+                    .collect();
+                shader.watch_files(vertex, fragment, self.builder.get_version(), &defines);
+            }
+        }
+        Ok(shader)
+    }
+}
@@ -0,0 +1,50 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+uniform sampler2D screenTexture;
+
+// the built-in effects of `PostProcessStack`, one variant per kind compiled with INVERSION, GRAYSCALE
+// or KERNEL defined, a plain copy without any of them
+#if defined(KERNEL)
+// 3x3 weights row by row from the top left
+uniform float kernel[9];
+// distance between the samples in texture coordinates
+uniform float offset;
+#endif
+
+void main()
+{
+#if defined(INVERSION)
+    vec3 col = texture(screenTexture, TexCoords).rgb;
+    FragColor = vec4(1.0 - col, 1.0);
+#elif defined(GRAYSCALE)
+    FragColor = texture(screenTexture, TexCoords);
+    float average = 0.2126 * FragColor.r + 0.7152 * FragColor.g + 0.0722 * FragColor.b;
+    FragColor = vec4(average, average, average, 1.0);
+#elif defined(KERNEL)
+    vec2 offsets[9] = vec2[](
+        vec2(-offset, offset), // top-left
+        vec2(0.0f, offset), // top-center
+        vec2(offset, offset), // top-right
+        vec2(-offset, 0.0f), // center-left
+        vec2(0.0f, 0.0f), // center-center
+        vec2(offset, 0.0f), // center-right
+        vec2(-offset, -offset), // bottom-left
+        vec2(0.0f, -offset), // bottom-center
+        vec2(offset, -offset)  // bottom-right
+    );
+
+    vec3 col = vec3(0.0);
+    for (int i = 0; i < 9; i++)
+    {
+        col += texture(screenTexture, TexCoords.st + offsets[i]).rgb * kernel[i];
+    }
+
+    FragColor = vec4(col, 1.0);
+#else
+    vec3 col = texture(screenTexture, TexCoords).rgb;
+    FragColor = vec4(col, 1.0);
+#endif
+}
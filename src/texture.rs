@@ -1,8 +1,15 @@
+use crate::gl_object::GlTexture;
+use crate::window::SharedGl;
 use glow::{
-    Context, HasContext, LINEAR, LINEAR_MIPMAP_LINEAR, REPEAT, RGBA, TEXTURE_2D,
-    TEXTURE_MAG_FILTER, TEXTURE_MIN_FILTER, TEXTURE_WRAP_S, TEXTURE_WRAP_T, UNSIGNED_BYTE,
+    Context, HasContext, CLAMP_TO_EDGE, FLOAT, LINEAR, LINEAR_MIPMAP_LINEAR, LINEAR_MIPMAP_NEAREST,
+    MAX_TEXTURE_MAX_ANISOTROPY_EXT, NEAREST, NEAREST_MIPMAP_LINEAR, NEAREST_MIPMAP_NEAREST, R8,
+    RED, REPEAT, RG, RG8, RGB, RGB16F, RGB8, RGBA, RGBA16F, RGBA32F, RGBA8, SRGB8, SRGB8_ALPHA8,
+    TEXTURE_2D, TEXTURE_CUBE_MAP, TEXTURE_CUBE_MAP_POSITIVE_X, TEXTURE_MAG_FILTER,
+    TEXTURE_MAX_ANISOTROPY_EXT, TEXTURE_MIN_FILTER, TEXTURE_WRAP_R, TEXTURE_WRAP_S, TEXTURE_WRAP_T,
+    UNPACK_ALIGNMENT, UNSIGNED_BYTE,
 };
-use image::GenericImageView;
+use image::{DynamicImage, GenericImageView, Rgb32FImage};
+use std::f32::consts::PI;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum TextureType {
@@ -12,45 +19,216 @@ pub enum TextureType {
     Height,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Deleted when dropped, share it with `Rc` instead of copying the handle.
+#[derive(Debug)]
 pub struct Texture {
-    raw: glow::Texture,
+    raw: GlTexture,
     file_name: String,
     ty: TextureType,
 }
 
+/// How many channels an 8 bit image is uploaded with, see `TextureOptions::channels`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextureChannels {
+    /// `R8`, from the image's luminance
+    R,
+    /// `RG8`, luminance and alpha
+    Rg,
+    Rgb,
+    Rgba,
+}
+
+/// How `Texture::from_image` uploads and samples an image, the default is what the tutorials
+/// mostly want: repeated, trilinear, no anisotropy, not flipped, linear `RGBA`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextureOptions {
+    pub wrap_s: u32,
+    pub wrap_t: u32,
+    pub min_filter: u32,
+    pub mag_filter: u32,
+    /// max samples of anisotropic filtering, 1 turns it off. Clamped to what the driver supports,
+    /// ignored without `EXT_texture_filter_anisotropic`.
+    pub anisotropy: f32,
+    pub generate_mipmaps: bool,
+    /// images start at the top row, OpenGL textures at the bottom one
+    pub flip_vertically: bool,
+    /// `SRGB8_ALPHA8`, or `SRGB8` for `Rgb`, so sampling converts to linear space.
+    /// There are no sRGB formats with one or two channels, those ignore it. WebGL2 can't generate
+    /// mipmaps of `SRGB8`, keep `Rgba` there.
+    pub srgb: bool,
+    pub channels: TextureChannels,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        Self {
+            wrap_s: REPEAT,
+            wrap_t: REPEAT,
+            min_filter: LINEAR_MIPMAP_LINEAR,
+            mag_filter: LINEAR,
+            anisotropy: 1.0,
+            generate_mipmaps: true,
+            flip_vertically: false,
+            srgb: false,
+            channels: TextureChannels::Rgba,
+        }
+    }
+}
+
+impl TextureOptions {
+    /// `CLAMP_TO_EDGE` in both directions, for textures with transparent borders like grass or windows.
+    pub fn clamp_to_edge() -> Self {
+        Self {
+            wrap_s: CLAMP_TO_EDGE,
+            wrap_t: CLAMP_TO_EDGE,
+            ..Self::default()
+        }
+    }
+
+    pub fn wrap(self, wrap: u32) -> Self {
+        Self {
+            wrap_s: wrap,
+            wrap_t: wrap,
+            ..self
+        }
+    }
+
+    pub fn filter(self, min_filter: u32, mag_filter: u32) -> Self {
+        Self {
+            min_filter,
+            mag_filter,
+            ..self
+        }
+    }
+
+    pub fn srgb(self, srgb: bool) -> Self {
+        Self { srgb, ..self }
+    }
+}
+
+/// How `Texture::from_image` uploads an image with some `TextureOptions`, see `TextureFormat::of`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TextureFormat {
+    pub internal_format: u32,
+    pub format: u32,
+    pub data_type: u32,
+    pub min_filter: u32,
+    pub mag_filter: u32,
+    pub generate_mipmaps: bool,
+}
+
+impl TextureFormat {
+    /// 8 bit images are uploaded with the channels and color space of `options`. Float images, e.g.
+    /// Radiance `.hdr` or OpenEXR files, keep their range and channels: `RGB16F` or `RGBA16F`, or
+    /// `RGBA32F` if a value doesn't fit a half float. Float textures have no mipmaps, WebGL2 can't
+    /// generate them without extensions, and on the web `RGBA32F` is sampled `NEAREST` since
+    /// filtering it needs `OES_texture_float_linear` there.
+    pub fn of(img: &DynamicImage, options: &TextureOptions) -> Self {
+        let (internal_format, format, data_type) = match img {
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+                if !fits_half_float(img) =>
+            {
+                (RGBA32F, RGBA, FLOAT)
+            }
+            DynamicImage::ImageRgb32F(_) => (RGB16F, RGB, FLOAT),
+            DynamicImage::ImageRgba32F(_) => (RGBA16F, RGBA, FLOAT),
+            _ => match options.channels {
+                TextureChannels::R => (R8, RED, UNSIGNED_BYTE),
+                TextureChannels::Rg => (RG8, RG, UNSIGNED_BYTE),
+                TextureChannels::Rgb if options.srgb => (SRGB8, RGB, UNSIGNED_BYTE),
+                TextureChannels::Rgb => (RGB8, RGB, UNSIGNED_BYTE),
+                TextureChannels::Rgba if options.srgb => (SRGB8_ALPHA8, RGBA, UNSIGNED_BYTE),
+                TextureChannels::Rgba => (RGBA8, RGBA, UNSIGNED_BYTE),
+            },
+        };
+        let generate_mipmaps = options.generate_mipmaps && data_type != FLOAT;
+        let (min_filter, mag_filter) = match (internal_format, generate_mipmaps) {
+            #[cfg(target_arch = "wasm32")]
+            (RGBA32F, _) => (NEAREST, NEAREST),
+            (_, false) => (without_mipmaps(options.min_filter), options.mag_filter),
+            (_, true) => (options.min_filter, options.mag_filter),
+        };
+        Self {
+            internal_format,
+            format,
+            data_type,
+            min_filter,
+            mag_filter,
+            generate_mipmaps,
+        }
+    }
+
+    /// The pixels of `img` converted to `format` and `data_type`.
+    fn pixels(&self, img: &DynamicImage) -> Vec<u8> {
+        match (self.format, self.data_type) {
+            (RGBA, FLOAT) => float_bytes(img.to_rgba32f().as_raw()),
+            (_, FLOAT) => float_bytes(img.to_rgb32f().as_raw()),
+            (RED, _) => img.to_luma8().into_raw(),
+            (RG, _) => img.to_luma_alpha8().into_raw(),
+            (RGB, _) => img.to_rgb8().into_raw(),
+            _ => img.to_rgba8().into_raw(),
+        }
+    }
+}
+
 impl Texture {
+    /// Uploads `img` in the format `TextureFormat::of` picks for `options`.
     pub fn from_image(
-        gl: &Context,
-        img: &image::DynamicImage,
+        gl: &SharedGl,
+        img: &DynamicImage,
         file_name: &str,
         ty: TextureType,
+        options: &TextureOptions,
     ) -> anyhow::Result<Self> {
-        // let img = img.flipv();
+        let flipped;
+        let img = if options.flip_vertically {
+            flipped = img.flipv();
+            &flipped
+        } else {
+            img
+        };
         let (width, height) = img.dimensions();
-        let data = img.to_rgba8();
+        let texture_format = TextureFormat::of(img, options);
+        let data = texture_format.pixels(img);
+        let TextureFormat {
+            internal_format,
+            format,
+            data_type,
+            min_filter,
+            mag_filter,
+            generate_mipmaps,
+        } = texture_format;
         let raw = unsafe {
-            let texture = gl
-                .create_texture()
+            let texture = GlTexture::new(gl)
                 .map_err(|e| anyhow::anyhow!("Failed to create texture: {:?}", e))?;
-            gl.bind_texture(TEXTURE_2D, Some(texture));
+            gl.bind_texture(TEXTURE_2D, Some(texture.raw()));
+            // rows of one or three bytes per pixel aren't 4 byte aligned
+            gl.pixel_store_i32(UNPACK_ALIGNMENT, 1);
             gl.tex_image_2d(
                 TEXTURE_2D,
                 0,
-                RGBA as i32,
+                internal_format as i32,
                 width as i32,
                 height as i32,
                 0,
-                RGBA,
-                UNSIGNED_BYTE,
+                format,
+                data_type,
                 Some(&data),
             );
-            gl.generate_mipmap(TEXTURE_2D);
+            gl.pixel_store_i32(UNPACK_ALIGNMENT, 4);
+            if generate_mipmaps {
+                gl.generate_mipmap(TEXTURE_2D);
+            }
 
-            gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_S, REPEAT as i32);
-            gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_T, REPEAT as i32);
-            gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MIN_FILTER, LINEAR_MIPMAP_LINEAR as i32);
-            gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MAG_FILTER, LINEAR as i32);
+            gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_S, options.wrap_s as i32);
+            gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_T, options.wrap_t as i32);
+            gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MIN_FILTER, min_filter as i32);
+            gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MAG_FILTER, mag_filter as i32);
+            if options.anisotropy > 1.0 && supports_anisotropy(gl) {
+                let max = gl.get_parameter_f32(MAX_TEXTURE_MAX_ANISOTROPY_EXT);
+                let anisotropy = options.anisotropy.min(max);
+                gl.tex_parameter_f32(TEXTURE_2D, TEXTURE_MAX_ANISOTROPY_EXT, anisotropy);
+            }
             gl.bind_texture(TEXTURE_2D, None);
 
             texture
@@ -60,28 +238,28 @@ impl Texture {
     }
 
     pub fn from_bytes(
-        gl: &Context,
+        gl: &SharedGl,
         bytes: &[u8],
         file_name: &str,
         ty: TextureType,
+        options: &TextureOptions,
     ) -> anyhow::Result<Self> {
         let img = image::load_from_memory(bytes).expect("Failed to load texture from bytes");
-        Self::from_image(gl, &img, file_name, ty)
+        Self::from_image(gl, &img, file_name, ty, options)
     }
 
     pub fn set_wrap_mode(&self, gl: &Context, wrap_s: i32, wrap_t: i32) {
         unsafe {
-            gl.bind_texture(TEXTURE_2D, Some(self.raw));
+            gl.bind_texture(TEXTURE_2D, Some(self.raw.raw()));
             gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_S, wrap_s);
             gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_WRAP_T, wrap_t);
             gl.bind_texture(TEXTURE_2D, None);
         }
     }
 
-    #[allow(dead_code)]
     pub fn set_filter_mode(&self, gl: &Context, min_filter: i32, mag_filter: i32) {
         unsafe {
-            gl.bind_texture(TEXTURE_2D, Some(self.raw));
+            gl.bind_texture(TEXTURE_2D, Some(self.raw.raw()));
             gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MIN_FILTER, min_filter);
             gl.tex_parameter_i32(TEXTURE_2D, TEXTURE_MAG_FILTER, mag_filter);
             gl.bind_texture(TEXTURE_2D, None);
@@ -93,28 +271,276 @@ impl Texture {
     }
 
     pub fn raw(&self) -> glow::Texture {
-        self.raw
+        self.raw.raw()
     }
 
-    #[allow(dead_code)]
     pub fn file_name(&self) -> &str {
         &self.file_name
     }
 
-    pub fn delete(&self, gl: &Context) {
+    pub fn bind(&self, gl: &Context, slot: u32) {
         unsafe {
-            gl.delete_texture(self.raw);
+            gl.active_texture(glow::TEXTURE0 + slot);
+            gl.bind_texture(TEXTURE_2D, Some(self.raw.raw()));
+        }
+    }
+}
+
+/// A cube map with mipmaps, sampled seamlessly across faces. Faces are in the order of
+/// `TEXTURE_CUBE_MAP_POSITIVE_X + i`: right, left, top, bottom, front and back.
+/// Float images, e.g. Radiance `.hdr` panoramas, are kept as `RGB16F`.
+#[derive(Debug)]
+pub struct CubemapTexture {
+    raw: GlTexture,
+    face_size: u32,
+}
+
+impl CubemapTexture {
+    /// Six square faces of the same size.
+    pub fn from_faces(gl: &SharedGl, faces: &[DynamicImage; 6]) -> anyhow::Result<Self> {
+        let face_size = faces[0].width();
+        for (i, face) in faces.iter().enumerate() {
+            if face.dimensions() != (face_size, face_size) {
+                anyhow::bail!(
+                    "Cubemap face {} is {}x{}, faces have to be square and {}x{} like the first",
+                    i,
+                    face.width(),
+                    face.height(),
+                    face_size,
+                    face_size
+                );
+            }
+        }
+        // one float face makes all of them float, so the faces match
+        let float = faces.iter().any(is_float);
+        let raw = unsafe {
+            let texture = GlTexture::new(gl)
+                .map_err(|e| anyhow::anyhow!("Failed to create texture: {:?}", e))?;
+            gl.bind_texture(TEXTURE_CUBE_MAP, Some(texture.raw()));
+            // rows of three bytes per pixel aren't 4 byte aligned
+            gl.pixel_store_i32(UNPACK_ALIGNMENT, 1);
+            for (i, face) in faces.iter().enumerate() {
+                let target = TEXTURE_CUBE_MAP_POSITIVE_X + i as u32;
+                let size = face_size as i32;
+                if float {
+                    let data = face.to_rgb32f();
+                    let bytes: &[u8] = bytemuck::cast_slice(data.as_raw());
+                    gl.tex_image_2d(
+                        target,
+                        0,
+                        RGB16F as i32,
+                        size,
+                        size,
+                        0,
+                        RGB,
+                        FLOAT,
+                        Some(bytes),
+                    );
+                } else {
+                    let data = face.to_rgb8();
+                    gl.tex_image_2d(
+                        target,
+                        0,
+                        RGB as i32,
+                        size,
+                        size,
+                        0,
+                        RGB,
+                        UNSIGNED_BYTE,
+                        Some(&data),
+                    );
+                }
+            }
+            gl.pixel_store_i32(UNPACK_ALIGNMENT, 4);
+            // WebGL2 can't generate mipmaps of float formats, they aren't color renderable there
+            let min_filter = if float {
+                LINEAR
+            } else {
+                gl.generate_mipmap(TEXTURE_CUBE_MAP);
+                LINEAR_MIPMAP_LINEAR
+            };
+            gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_MIN_FILTER, min_filter as i32);
+            gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_MAG_FILTER, LINEAR as i32);
+            gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_S, CLAMP_TO_EDGE as i32);
+            gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_T, CLAMP_TO_EDGE as i32);
+            gl.tex_parameter_i32(TEXTURE_CUBE_MAP, TEXTURE_WRAP_R, CLAMP_TO_EDGE as i32);
+            gl.bind_texture(TEXTURE_CUBE_MAP, None);
+            texture
+        };
+        Ok(Self { raw, face_size })
+    }
+
+    /// Picks the layout by the aspect ratio: 4:3 for `from_cross` horizontally, 3:4 vertically,
+    /// and 2:1 for `from_equirectangular` with faces a quarter of the width.
+    pub fn from_image(gl: &SharedGl, img: &DynamicImage) -> anyhow::Result<Self> {
+        let (width, height) = img.dimensions();
+        if width * 3 == height * 4 || width * 4 == height * 3 {
+            Self::from_cross(gl, img)
+        } else if width == height * 2 {
+            Self::from_equirectangular(gl, img, width / 4)
+        } else {
+            anyhow::bail!(
+                "Cannot tell the cubemap layout of a {}x{} image, expected a 4:3 or 3:4 cross or a 2:1 panorama",
+                width,
+                height
+            )
+        }
+    }
+
+    /// A single image of the unfolded cube, either horizontal with the top and bottom above and below the front
+    /// ```text
+    ///       top
+    /// left front right back
+    ///      bottom
+    /// ```
+    /// or vertical with the back below the bottom, upside down.
+    pub fn from_cross(gl: &SharedGl, img: &DynamicImage) -> anyhow::Result<Self> {
+        Self::from_faces(gl, &Self::cross_faces(img)?)
+    }
+
+    /// The faces of a cross for `from_cross`, in the order of `from_faces`.
+    pub fn cross_faces(img: &DynamicImage) -> anyhow::Result<[DynamicImage; 6]> {
+        let (width, height) = img.dimensions();
+        let horizontal = width * 3 == height * 4;
+        if !horizontal && width * 4 != height * 3 {
+            anyhow::bail!(
+                "A cubemap cross has to be 4:3 or 3:4, not {}x{}",
+                width,
+                height
+            );
         }
+        let size = if horizontal { width / 4 } else { width / 3 };
+        let face = |column: u32, row: u32| img.crop_imm(column * size, row * size, size, size);
+        let back = if horizontal {
+            face(3, 1)
+        } else {
+            face(1, 3).rotate180()
+        };
+        Ok([
+            face(2, 1),
+            face(0, 1),
+            face(1, 0),
+            face(1, 2),
+            face(1, 1),
+            back,
+        ])
+    }
+
+    /// A 2:1 panorama with longitude along the width and latitude along the height,
+    /// resampled on the CPU into faces of `face_size`.
+    pub fn from_equirectangular(
+        gl: &SharedGl,
+        img: &DynamicImage,
+        face_size: u32,
+    ) -> anyhow::Result<Self> {
+        Self::from_faces(gl, &Self::equirectangular_faces(img, face_size))
+    }
+
+    /// The faces of a panorama for `from_equirectangular`, in the order of `from_faces`.
+    /// Float panoramas give float faces, others 8 bit ones.
+    pub fn equirectangular_faces(img: &DynamicImage, face_size: u32) -> [DynamicImage; 6] {
+        let panorama = img.to_rgb32f();
+        std::array::from_fn(|i| {
+            let face = Rgb32FImage::from_fn(face_size, face_size, |x, y| {
+                // texel center in [-1, 1], t grows downwards like the rows
+                let s = 2.0 * (x as f32 + 0.5) / face_size as f32 - 1.0;
+                let t = 2.0 * (y as f32 + 0.5) / face_size as f32 - 1.0;
+                // direction through the texel, see the cube map face selection table of the GL spec
+                let (dx, dy, dz) = match i {
+                    0 => (1.0, -t, -s),
+                    1 => (-1.0, -t, s),
+                    2 => (s, 1.0, t),
+                    3 => (s, -1.0, -t),
+                    4 => (s, -t, 1.0),
+                    _ => (-s, -t, -1.0),
+                };
+                let length = (dx * dx + dy * dy + dz * dz).sqrt();
+                let u = 0.5 + dz.atan2(dx) / (2.0 * PI);
+                let v = 0.5 - (dy / length).asin() / PI;
+                sample_bilinear(&panorama, u, v)
+            });
+            let face = DynamicImage::ImageRgb32F(face);
+            if is_float(img) {
+                face
+            } else {
+                DynamicImage::ImageRgb8(face.to_rgb8())
+            }
+        })
+    }
+
+    pub fn raw(&self) -> glow::Texture {
+        self.raw.raw()
+    }
+
+    pub fn face_size(&self) -> u32 {
+        self.face_size
     }
 
     pub fn bind(&self, gl: &Context, slot: u32) {
         unsafe {
             gl.active_texture(glow::TEXTURE0 + slot);
-            gl.bind_texture(TEXTURE_2D, Some(self.raw));
+            gl.bind_texture(TEXTURE_CUBE_MAP, Some(self.raw.raw()));
         }
     }
 }
 
+/// The filter a mipmapped min filter uses on the base level alone.
+fn without_mipmaps(min_filter: u32) -> u32 {
+    match min_filter {
+        NEAREST_MIPMAP_NEAREST | NEAREST_MIPMAP_LINEAR => NEAREST,
+        LINEAR_MIPMAP_NEAREST | LINEAR_MIPMAP_LINEAR => LINEAR,
+        filter => filter,
+    }
+}
+
+/// `GL_EXT_texture_filter_anisotropic` on desktop, `EXT_texture_filter_anisotropic` on WebGL2.
+fn supports_anisotropy(gl: &Context) -> bool {
+    gl.supported_extensions()
+        .iter()
+        .any(|extension| extension.ends_with("texture_filter_anisotropic"))
+}
+
+/// Whether every value of a float image fits the range of a half float.
+fn fits_half_float(img: &DynamicImage) -> bool {
+    const HALF_MAX: f32 = 65504.0;
+    let values = match img {
+        DynamicImage::ImageRgb32F(data) => data.as_raw(),
+        DynamicImage::ImageRgba32F(data) => data.as_raw(),
+        _ => return true,
+    };
+    values.iter().all(|value| value.abs() <= HALF_MAX)
+}
+
+fn float_bytes(values: &[f32]) -> Vec<u8> {
+    bytemuck::cast_slice(values).to_vec()
+}
+
+fn is_float(img: &DynamicImage) -> bool {
+    matches!(
+        img,
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+    )
+}
+
+/// Samples at `u`, `v` in [0, 1], wrapping around horizontally.
+fn sample_bilinear(img: &Rgb32FImage, u: f32, v: f32) -> image::Rgb<f32> {
+    let (width, height) = img.dimensions();
+    let x = u * width as f32 - 0.5;
+    let y = (v * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let column = |x: f32| (x as i64).rem_euclid(width as i64) as u32;
+    let row = |y: f32| (y as u32).min(height - 1);
+    let texel = |x: f32, y: f32| img.get_pixel(column(x), row(y)).0;
+    let (a, b) = (texel(x0, y0), texel(x0 + 1.0, y0));
+    let (c, d) = (texel(x0, y0 + 1.0), texel(x0 + 1.0, y0 + 1.0));
+    image::Rgb(std::array::from_fn(|i| {
+        let top = a[i] + (b[i] - a[i]) * fx;
+        let bottom = c[i] + (d[i] - c[i]) * fx;
+        top + (bottom - top) * fy
+    }))
+}
+
 pub fn map_texture_type_to_string(ty: TextureType) -> String {
     match ty {
         TextureType::Diffuse => "texture_diffuse".to_string(),
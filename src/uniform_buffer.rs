@@ -0,0 +1,229 @@
+use crate::gl_object::GlBuffer;
+use crate::shader::MyShader;
+use crate::window::SharedGl;
+use glow::{Context, HasContext, DYNAMIC_DRAW, UNIFORM_BUFFER};
+use nalgebra_glm as glm;
+use std::marker::PhantomData;
+
+/// A value laid out by the std140 rules of `layout (std140) uniform` blocks.
+/// Implemented for scalars, glm vectors and matrices, arrays of those,
+/// and structs declared with `uniform_block!`.
+pub trait Std140 {
+    /// base alignment in bytes
+    const ALIGN: usize;
+
+    /// Appends the value, `out` is already padded to `ALIGN`.
+    fn write_std140(&self, out: &mut Vec<u8>);
+}
+
+/// Pads `out` with zeros up to a multiple of `align`.
+pub(crate) fn pad_std140(out: &mut Vec<u8>, align: usize) {
+    out.resize(out.len().next_multiple_of(align), 0);
+}
+
+/// The bytes of `value` as a uniform buffer expects them.
+pub fn std140_bytes<T: Std140>(value: &T) -> Vec<u8> {
+    let mut out = vec![];
+    value.write_std140(&mut out);
+    pad_std140(&mut out, T::ALIGN);
+    out
+}
+
+macro_rules! impl_std140_scalar {
+    ($($ty:ty => |$value:ident| $bytes:expr;)*) => {
+        $(
+            impl Std140 for $ty {
+                const ALIGN: usize = 4;
+
+                fn write_std140(&self, out: &mut Vec<u8>) {
+                    let $value = *self;
+                    out.extend_from_slice(&$bytes.to_ne_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_std140_scalar! {
+    f32 => |value| value;
+    i32 => |value| value;
+    u32 => |value| value;
+    // a GLSL bool takes 4 bytes like uint
+    bool => |value| value as u32;
+}
+
+macro_rules! impl_std140_vector {
+    ($($ty:ty, $align:expr;)*) => {
+        $(
+            impl Std140 for $ty {
+                const ALIGN: usize = $align;
+
+                fn write_std140(&self, out: &mut Vec<u8>) {
+                    for component in self.iter() {
+                        component.write_std140(out);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+// vec3 aligns like vec4 but only takes 12 bytes
+impl_std140_vector! {
+    glm::Vec2, 8;
+    glm::Vec3, 16;
+    glm::Vec4, 16;
+    glm::IVec2, 8;
+    glm::IVec3, 16;
+    glm::IVec4, 16;
+    glm::UVec2, 8;
+    glm::UVec3, 16;
+    glm::UVec4, 16;
+}
+
+/// Matrices are arrays of column vectors, each padded to a vec4.
+macro_rules! impl_std140_matrix {
+    ($($ty:ty;)*) => {
+        $(
+            impl Std140 for $ty {
+                const ALIGN: usize = 16;
+
+                fn write_std140(&self, out: &mut Vec<u8>) {
+                    for column in self.column_iter() {
+                        pad_std140(out, 16);
+                        for component in column.iter() {
+                            component.write_std140(out);
+                        }
+                    }
+                    pad_std140(out, 16);
+                }
+            }
+        )*
+    };
+}
+
+impl_std140_matrix! {
+    glm::Mat2;
+    glm::Mat3;
+    glm::Mat4;
+}
+
+/// Array elements are padded to a vec4 each.
+impl<T: Std140, const N: usize> Std140 for [T; N] {
+    const ALIGN: usize = 16;
+
+    fn write_std140(&self, out: &mut Vec<u8>) {
+        for element in self {
+            pad_std140(out, 16);
+            element.write_std140(out);
+        }
+        pad_std140(out, 16);
+    }
+}
+
+/// Declares a struct mirroring a std140 uniform block and implements `Std140`
+/// for it, members are laid out in declaration order with the GLSL padding in between.
+/// Structs may nest, like structs in GLSL blocks.
+#[macro_export]
+macro_rules! uniform_block {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl $crate::Std140 for $name {
+            const ALIGN: usize = 16;
+
+            fn write_std140(&self, out: &mut Vec<u8>) {
+                $(
+                    let align = <$ty as $crate::Std140>::ALIGN;
+                    out.resize(out.len().next_multiple_of(align), 0);
+                    $crate::Std140::write_std140(&self.$field, out);
+                )*
+                out.resize(out.len().next_multiple_of(16), 0);
+            }
+        }
+    };
+}
+
+/// A uniform buffer holding a `T`, bound to a binding point that shaders' blocks are tied to
+/// with `bind_block`. `set` only uploads when the value changed.
+pub struct UniformBuffer<T: Std140> {
+    buffer: GlBuffer,
+    binding: u32,
+    /// bytes last uploaded
+    bytes: Vec<u8>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Std140> UniformBuffer<T> {
+    pub fn new(gl: &SharedGl, binding: u32, value: &T) -> Self {
+        let bytes = std140_bytes(value);
+        let buffer = unsafe {
+            let buffer = GlBuffer::new(gl).expect("Cannot create uniform buffer");
+            gl.bind_buffer(UNIFORM_BUFFER, Some(buffer.raw()));
+            gl.buffer_data_u8_slice(UNIFORM_BUFFER, &bytes, DYNAMIC_DRAW);
+            gl.bind_buffer(UNIFORM_BUFFER, None);
+            gl.bind_buffer_base(UNIFORM_BUFFER, binding, Some(buffer.raw()));
+            buffer
+        };
+        Self {
+            buffer,
+            binding,
+            bytes,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Ties the uniform block `block_name` of `shader` to this buffer's binding point.
+    /// Panics when the shader has no such block or the block is bigger than `T`,
+    /// which means the Rust struct is missing members.
+    pub fn bind_block(&self, gl: &Context, shader: &MyShader, block_name: &str) {
+        unsafe {
+            let index = gl
+                .get_uniform_block_index(shader.program(), block_name)
+                .unwrap_or_else(|| panic!("Cannot find uniform block {}", block_name));
+            let size = gl.get_active_uniform_block_parameter_i32(
+                shader.program(),
+                index,
+                glow::UNIFORM_BLOCK_DATA_SIZE,
+            ) as usize;
+            if size > self.bytes.len() {
+                panic!(
+                    "Uniform block {} takes {} bytes but {} only has {}",
+                    block_name,
+                    size,
+                    std::any::type_name::<T>(),
+                    self.bytes.len()
+                );
+            }
+            gl.uniform_block_binding(shader.program(), index, self.binding);
+        }
+    }
+
+    pub fn set(&mut self, gl: &Context, value: &T) {
+        let bytes = std140_bytes(value);
+        if bytes == self.bytes {
+            return;
+        }
+        unsafe {
+            gl.bind_buffer(UNIFORM_BUFFER, Some(self.buffer.raw()));
+            gl.buffer_sub_data_u8_slice(UNIFORM_BUFFER, 0, &bytes);
+            gl.bind_buffer(UNIFORM_BUFFER, None);
+        }
+        self.bytes = bytes;
+    }
+
+    /// Binds the buffer to its binding point again, e.g. after another buffer took it.
+    pub fn bind(&self, gl: &Context) {
+        unsafe {
+            gl.bind_buffer_base(UNIFORM_BUFFER, self.binding, Some(self.buffer.raw()));
+        }
+    }
+}
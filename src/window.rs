@@ -1,6 +1,10 @@
+use crate::clock::{Clock, FixedStepClock, SystemClock};
 use glow::{Context, HasContext};
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use typed_builder::TypedBuilder;
 use winit_input_helper::WinitInputHelper;
@@ -10,27 +14,44 @@ pub const UPDATE_PER_SECOND: usize = 240;
 pub const FPS: usize = 60;
 #[allow(dead_code)]
 pub const TIME_STEP: Duration = Duration::from_nanos(1_000_000_000 / FPS as u64);
+/// frames rendered when running headless or taking a screenshot without `WindowInitInfo::frames`
+pub const DEFAULT_FRAMES: u32 = 1;
 
 pub struct Game<A: Application> {
     input: WinitInputHelper,
     app: A,
     ctx: AppContext,
+    frame_limit: Option<u32>,
+    frames_rendered: u32,
+    screenshot: Option<PathBuf>,
+}
+
+impl<A: Application> Game<A> {
+    unsafe fn exit(&mut self) {
+        log::info!("Exiting");
+        self.app.exit(&self.ctx);
+        #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+        if let Some(egui_glow) = self.ctx.egui_glow.as_mut() {
+            egui_glow.destroy();
+        }
+    }
 }
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
 #[derive(Debug)]
 pub enum UserEvent {
-    Redraw(Duration),
+    Redraw(#[allow(dead_code)] Duration),
 }
 
 pub trait Application: Sized {
     async unsafe fn new(_ctx: &AppContext) -> Self;
     #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
-    fn ui(&mut self, state: &AppState, _gl_ctx: &GLContext, egui_ctx: &egui::Context) {
+    fn ui(&mut self, state: &AppState, gl_ctx: &GLContext, egui_ctx: &egui::Context) {
         // show fps by default
         egui::Window::new("Info").show(egui_ctx, |ui| {
             ui.label(format!("FPS: {:.1}", 1.0 / state.render_delta_time));
         });
+        crate::shader_reflection::shader_window(&gl_ctx.gl, egui_ctx);
     }
     #[cfg(all(not(target_arch = "wasm32"), feature = "imgui-support"))]
     fn ui(&mut self, _ui: &easy_imgui_window::easy_imgui::Ui<EasyImGuiFacade<Self>>) {}
@@ -44,6 +65,66 @@ pub trait Application: Sized {
     unsafe fn exit(&mut self, _ctx: &AppContext) {}
 }
 
+/// Object safe `Application`, so the launcher can host whichever tutorial is picked.
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+pub(crate) trait AnyApplication {
+    fn ui(&mut self, state: &AppState, gl_ctx: &GLContext, egui_ctx: &egui::Context);
+    unsafe fn render(&mut self, ctx: &AppContext);
+    unsafe fn update(&mut self, update_delta_time: f32);
+    unsafe fn resize(&mut self, ctx: &AppContext, width: u32, height: u32);
+    unsafe fn process_input(&mut self, ctx: &AppContext, input: &WinitInputHelper);
+    unsafe fn exit(&mut self, ctx: &AppContext);
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+impl<A: Application> AnyApplication for A {
+    fn ui(&mut self, state: &AppState, gl_ctx: &GLContext, egui_ctx: &egui::Context) {
+        Application::ui(self, state, gl_ctx, egui_ctx)
+    }
+    unsafe fn render(&mut self, ctx: &AppContext) {
+        Application::render(self, ctx)
+    }
+    unsafe fn update(&mut self, update_delta_time: f32) {
+        Application::update(self, update_delta_time)
+    }
+    unsafe fn resize(&mut self, ctx: &AppContext, width: u32, height: u32) {
+        Application::resize(self, ctx, width, height)
+    }
+    unsafe fn process_input(&mut self, ctx: &AppContext, input: &WinitInputHelper) {
+        Application::process_input(self, ctx, input)
+    }
+    unsafe fn exit(&mut self, ctx: &AppContext) {
+        Application::exit(self, ctx)
+    }
+}
+
+/// Creates an `Application` in an existing context, see `capture_app`.
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+pub(crate) type AppConstructor = unsafe fn(&AppContext) -> Box<dyn AnyApplication>;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+unsafe fn new_any_app<A: Application + 'static>(ctx: &AppContext) -> Box<dyn AnyApplication> {
+    Box::new(pollster::block_on(A::new(ctx)))
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+thread_local! {
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    static CAPTURED: RefCell<Option<(WindowInitInfo, AppConstructor)>> = const { RefCell::new(None) };
+}
+
+/// Drives a tutorial main function with `run` returning right away instead of opening a window,
+/// handing back what it was called with. `None` if the tutorial doesn't go through `run`.
+#[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+pub(crate) fn capture_app(
+    main: impl std::future::Future<Output = ()>,
+) -> Option<(WindowInitInfo, AppConstructor)> {
+    CAPTURING.set(true);
+    pollster::block_on(main);
+    CAPTURING.set(false);
+    CAPTURED.take()
+}
+
 #[derive(TypedBuilder, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct WindowInitInfo {
     #[builder(default = 800)]
@@ -58,11 +139,117 @@ pub struct WindowInitInfo {
     pub minor: u8,
     #[builder(default = 1)]
     pub num_samples: u8,
+    /// wait for vblank on swap, off also lifts the 60 FPS cap, e.g. for profiling
+    #[builder(default = true)]
+    pub vsync: bool,
+    /// render into an offscreen pbuffer instead of a window, see `run_headless`
+    #[builder(default)]
+    pub headless: bool,
+    /// number of frames to render before exiting, a window without it runs until closed
+    #[builder(default)]
+    pub frames: Option<u32>,
+    /// save the default framebuffer as PNG after the last frame, see `AppContext::capture_frame`
+    #[builder(default)]
+    pub screenshot: Option<PathBuf>,
+    /// replace the wall clock with a `FixedStepClock` advancing this much per frame
+    #[builder(default)]
+    pub time_step: Option<Duration>,
+}
+
+impl WindowInitInfo {
+    fn frame_limit(&self) -> Option<u32> {
+        if self.headless || self.screenshot.is_some() {
+            Some(self.frames.unwrap_or(DEFAULT_FRAMES))
+        } else {
+            self.frames
+        }
+    }
+}
+
+/// Overrides applied to every `WindowInitInfo` passed to `run`, so the binary and tests can change
+/// how a tutorial is launched without touching the tutorial itself.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct LaunchOptions {
+    pub headless: bool,
+    pub frames: Option<u32>,
+    pub screenshot: Option<PathBuf>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub time_step: Option<Duration>,
+    pub title: Option<String>,
+    pub major: Option<u8>,
+    pub minor: Option<u8>,
+    pub num_samples: Option<u8>,
+    pub vsync: Option<bool>,
+    /// seeds the tutorials that scatter things randomly, e.g. the asteroid fields, so their frames
+    /// are the same on every run. They are random otherwise.
+    pub seed: Option<u64>,
+}
+
+static LAUNCH_OPTIONS: Mutex<Option<LaunchOptions>> = Mutex::new(None);
+
+pub fn set_launch_options(options: LaunchOptions) {
+    *LAUNCH_OPTIONS.lock().unwrap() = Some(options);
+}
+
+pub(crate) fn launched_headless() -> bool {
+    LAUNCH_OPTIONS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|options| options.headless)
+}
+
+pub(crate) fn launch_seed() -> Option<u64> {
+    LAUNCH_OPTIONS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|options| options.seed)
+}
+
+impl LaunchOptions {
+    fn apply(&self, init_info: &mut WindowInitInfo) {
+        if self.headless {
+            init_info.headless = true;
+        }
+        if let Some(frames) = self.frames {
+            init_info.frames = Some(frames);
+        }
+        if let Some(screenshot) = &self.screenshot {
+            init_info.screenshot = Some(screenshot.clone());
+        }
+        if let Some(width) = self.width {
+            init_info.width = width;
+        }
+        if let Some(height) = self.height {
+            init_info.height = height;
+        }
+        if let Some(time_step) = self.time_step {
+            init_info.time_step = Some(time_step);
+        }
+        if let Some(title) = &self.title {
+            init_info.title = title.clone();
+        }
+        if let Some(major) = self.major {
+            init_info.major = major;
+        }
+        if let Some(minor) = self.minor {
+            init_info.minor = minor;
+        }
+        if let Some(num_samples) = self.num_samples {
+            init_info.num_samples = num_samples;
+        }
+        if let Some(vsync) = self.vsync {
+            init_info.vsync = vsync;
+        }
+    }
 }
 
 pub struct AppContext {
+    /// `None` when running headless, there is no event loop to drive egui
     #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
-    pub egui_glow: egui_glow::EguiGlow,
+    pub egui_glow: Option<egui_glow::EguiGlow>,
     #[cfg(feature = "imgui-support")]
     pub imgui_renderer: easy_imgui_window::easy_imgui_renderer::Renderer,
     #[cfg(feature = "imgui-support")]
@@ -72,14 +259,22 @@ pub struct AppContext {
     pub gl_state: GlState,
 }
 
+/// The context shared with egui or imgui and the `GlObject`s that delete themselves.
+#[cfg(feature = "imgui-support")]
+pub type SharedGl = std::rc::Rc<glow::Context>;
+#[cfg(not(feature = "imgui-support"))]
+pub type SharedGl = Arc<glow::Context>;
+
 pub struct GLContext {
-    #[cfg(feature = "imgui-support")]
-    pub gl: std::rc::Rc<glow::Context>,
-    #[cfg(not(feature = "imgui-support"))]
-    pub gl: Arc<glow::Context>,
+    pub gl: SharedGl,
 
+    /// `None` when running headless, the pbuffer surface is owned by `run_headless`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub gl_surface: Option<glutin::surface::Surface<glutin::surface::WindowSurface>>,
+    /// `None` when running headless like `gl_surface`. Kept here so that the context
+    /// is destroyed after the application and the `GlObject`s it drops.
     #[cfg(not(target_arch = "wasm32"))]
-    pub gl_surface: glutin::surface::Surface<glutin::surface::WindowSurface>,
+    pub context: Option<glutin::context::PossiblyCurrentContext>,
 }
 
 pub struct AppState {
@@ -92,6 +287,7 @@ pub struct AppState {
     pub last_render_time: chrono::DateTime<chrono::Utc>,
     pub update_delta_time: f32,
     pub render_delta_time: f32,
+    pub clock: Box<dyn Clock>,
 }
 
 #[derive(Debug, Default)]
@@ -101,7 +297,7 @@ pub struct GlState {
 
 #[allow(dead_code)]
 impl AppContext {
-    pub fn gl(&self) -> &glow::Context {
+    pub fn gl(&self) -> &SharedGl {
         &self.gl_context.gl
     }
 
@@ -143,10 +339,84 @@ impl AppContext {
     pub fn suggested_shader_version(&self) -> &'static str {
         self.app_state.suggested_shader_version
     }
+
+    /// Reads back the color buffer of `framebuffer`, or the default framebuffer when `None`.
+    /// Rows are flipped so the image is upright, multisampled FBOs must be resolved first.
+    pub fn read_frame(&self, framebuffer: Option<glow::Framebuffer>) -> image::RgbaImage {
+        let gl = self.gl();
+        let (width, height) = (self.width(), self.height());
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        unsafe {
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, framebuffer);
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            gl.read_pixels(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(&mut pixels),
+            );
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+        }
+        let mut img = image::RgbaImage::from_raw(width, height, pixels)
+            .expect("Pixel buffer matches the frame size");
+        image::imageops::flip_vertical_in_place(&mut img);
+        if framebuffer.is_none() {
+            // the window is presented opaque whatever ends up in the alpha channel
+            img.pixels_mut().for_each(|p| p[3] = 255);
+        }
+        img
+    }
+
+    /// Saves the color buffer of `framebuffer`, or the default framebuffer when `None`, as PNG.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture_frame(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        framebuffer: Option<glow::Framebuffer>,
+    ) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        self.read_frame(framebuffer)
+            .save_with_format(path, image::ImageFormat::Png)?;
+        log::info!("Saved frame to {:?}", path);
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
 impl AppState {
+    fn new(
+        suggested_shader_version: &'static str,
+        width: u32,
+        height: u32,
+        scale_factor: f64,
+        time_step: Option<Duration>,
+    ) -> Self {
+        let clock: Box<dyn Clock> = match time_step {
+            Some(step) => Box::new(FixedStepClock::new(step)),
+            None => Box::new(SystemClock),
+        };
+        let now = clock.now();
+        Self {
+            suggested_shader_version,
+            width,
+            height,
+            scale_factor,
+            start: now,
+            last_update_time: now,
+            last_render_time: now,
+            update_delta_time: 0.0,
+            render_delta_time: 0.0,
+            clock,
+        }
+    }
+
+    pub fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.clock.now()
+    }
+
     pub fn start(&self) -> chrono::DateTime<chrono::Utc> {
         self.start
     }
@@ -168,7 +438,7 @@ impl AppState {
     }
 
     pub fn elapsed_time(&self) -> chrono::Duration {
-        chrono::Utc::now() - self.start
+        self.now() - self.start
     }
 
     pub fn elapsed_time_secs(&self) -> f32 {
@@ -179,6 +449,13 @@ impl AppState {
         self.suggested_shader_version
     }
 
+    fn tick_render_time(&mut self) {
+        self.clock.advance();
+        let now = self.now();
+        self.render_delta_time = (now - self.last_render_time).num_milliseconds() as f32 / 1000.0;
+        self.last_render_time = now;
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -188,10 +465,30 @@ impl AppState {
     }
 }
 
-pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
+pub async unsafe fn run<App: Application + 'static>(mut init_info: WindowInitInfo) {
+    #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
+    if CAPTURING.get() {
+        CAPTURED.set(Some((init_info, new_any_app::<App>)));
+        return;
+    }
+
+    if let Some(options) = LAUNCH_OPTIONS.lock().unwrap().as_ref() {
+        options.apply(&mut init_info);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if init_info.headless {
+        run_headless::<App>(init_info).await;
+        return;
+    }
+    #[cfg(target_arch = "wasm32")]
+    if init_info.headless {
+        log::warn!("Headless mode is not supported on the web, opening a canvas instead");
+    }
+
     let width = init_info.width;
     let height = init_info.height;
-    let title = init_info.title;
+    let title = init_info.title.clone();
 
     // Create a context from a WebGL2 context on wasm32 targets
     #[cfg(target_arch = "wasm32")]
@@ -319,16 +616,25 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
         {
             set_debug_callback(&mut gl);
         }
+        unsafe { crate::program_cache::load_functions(&gl, |s| gl_display.get_proc_address(s)) };
+        // filter cubemaps across the edges of faces instead of within each face, WebGL2 always does
+        unsafe { gl.enable(glow::TEXTURE_CUBE_MAP_SEAMLESS) };
 
-        gl_surface
-            .set_swap_interval(&gl_context, SwapInterval::Wait(NonZeroU32::new(1).unwrap()))
-            .unwrap();
+        let swap_interval = if init_info.vsync {
+            SwapInterval::Wait(NonZeroU32::new(1).unwrap())
+        } else {
+            SwapInterval::DontWait
+        };
+        if let Err(e) = gl_surface.set_swap_interval(&gl_context, swap_interval) {
+            log::warn!("Cannot set swap interval {:?}: {:?}", swap_interval, e);
+        }
 
+        let shader_version = shader_version(&gl, major, minor);
         (
             gl,
             gl_surface,
             gl_context,
-            "#version 330 core",
+            shader_version,
             window,
             event_loop,
         )
@@ -342,10 +648,10 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
         }
     }
     #[cfg(feature = "imgui-support")]
-    #[allow(clippy::arc_with_non_send_sync)]
     let gl = std::rc::Rc::new(gl);
 
     #[cfg(not(feature = "imgui-support"))]
+    #[allow(clippy::arc_with_non_send_sync)]
     let gl = Arc::new(gl);
 
     #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
@@ -360,7 +666,7 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
                     .send_event(UserEvent::Redraw(info.delay))
                     .expect("Cannot send event");
             });
-        egui_glow
+        Some(egui_glow)
     };
     #[cfg(feature = "imgui-support")]
     let imgui_renderer = {
@@ -380,19 +686,17 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
         gl_context: GLContext {
             gl,
             #[cfg(not(target_arch = "wasm32"))]
-            gl_surface,
+            gl_surface: Some(gl_surface),
+            #[cfg(not(target_arch = "wasm32"))]
+            context: Some(gl_context),
         },
-        app_state: AppState {
-            suggested_shader_version: shader_version,
+        app_state: AppState::new(
+            shader_version,
             width,
             height,
             scale_factor,
-            start: chrono::Utc::now(),
-            last_update_time: chrono::Utc::now(),
-            last_render_time: chrono::Utc::now(),
-            update_delta_time: 0.0,
-            render_delta_time: 0.0,
-        },
+            init_info.time_step,
+        ),
         #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
         egui_glow,
         gl_state: GlState::default(),
@@ -404,9 +708,14 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
         input: WinitInputHelper::new(),
         app,
         ctx,
+        frame_limit: init_info.frame_limit(),
+        frames_rendered: 0,
+        screenshot: init_info.screenshot,
     };
 
     let window = Arc::new(window);
+    #[cfg(not(target_arch = "wasm32"))]
+    let vsync = init_info.vsync;
 
     game_loop::game_loop(
         event_loop,
@@ -415,29 +724,36 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
         UPDATE_PER_SECOND as u32,
         0.1,
         move |g| {
-            let ctx = &mut g.game.ctx;
-            let now = chrono::Utc::now();
-            ctx.app_state.update_delta_time =
-                (now - ctx.app_state.last_update_time).num_milliseconds() as f32 / 1000.0;
-            ctx.app_state.last_update_time = chrono::Utc::now();
-            g.game.app.update(ctx.app_state.update_delta_time);
+            update_app(&mut g.game.app, &mut g.game.ctx);
         },
         move |g| {
             let ctx = &mut g.game.ctx;
-            let now = chrono::Utc::now();
             let app = &mut g.game.app;
-            ctx.app_state.render_delta_time =
-                (now - ctx.app_state.last_render_time).num_milliseconds() as f32 / 1000.0;
-            ctx.app_state.last_render_time = chrono::Utc::now();
+            ctx.app_state.tick_render_time();
             #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
-            ctx.egui_glow.run(&g.window, |egui_ctx| {
-                app.ui(&ctx.app_state, &ctx.gl_context, egui_ctx);
-            });
+            if let Some(egui_glow) = ctx.egui_glow.as_mut() {
+                egui_glow.run(&g.window, |egui_ctx| {
+                    app.ui(&ctx.app_state, &ctx.gl_context, egui_ctx);
+                });
+            }
 
             let gl = &ctx.gl_context.gl;
             restore_gl_states(gl, &ctx.gl_state.states);
             app.render(ctx);
 
+            g.game.frames_rendered += 1;
+            let last_frame = g
+                .game
+                .frame_limit
+                .is_some_and(|limit| g.game.frames_rendered >= limit);
+            #[cfg(not(target_arch = "wasm32"))]
+            if let (true, Some(path)) = (last_frame, &g.game.screenshot) {
+                // before ui and swap, so only the tutorial itself ends up in the image
+                if let Err(e) = ctx.capture_frame(path, None) {
+                    log::error!("Failed to save screenshot {:?}: {:?}", path, e);
+                }
+            }
+
             // we have debug callback already
             // #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
             // {
@@ -469,18 +785,29 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
                 record_gl_states(gl, &mut ctx.gl_state.states);
 
                 #[cfg(feature = "egui-support")]
-                ctx.egui_glow.paint(&g.window);
+                if let Some(egui_glow) = ctx.egui_glow.as_mut() {
+                    egui_glow.paint(&g.window);
+                }
 
                 use game_loop::TimeTrait;
                 use glutin::surface::GlSurface;
 
-                ctx.gl_context.gl_surface.swap_buffers(&gl_context).unwrap();
+                if let (Some(gl_surface), Some(context)) =
+                    (&ctx.gl_context.gl_surface, &ctx.gl_context.context)
+                {
+                    gl_surface.swap_buffers(context).unwrap();
+                }
 
                 let dt = TIME_STEP.as_secs_f64() - game_loop::Time::now().sub(&g.current_instant());
-                if dt > 0.0 {
+                if vsync && dt > 0.0 {
                     std::thread::sleep(Duration::from_secs_f64(dt));
                 }
             }
+
+            if last_frame {
+                g.game.exit();
+                g.exit();
+            }
         },
         move |g, event| {
             let input = &mut g.game.input;
@@ -508,10 +835,7 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
                     || input.close_requested()
                     || input.destroyed()
                 {
-                    log::info!("Exiting");
-                    app.exit(ctx);
-                    #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
-                    ctx.egui_glow.destroy();
+                    g.game.exit();
                     g.exit();
                     return;
                 }
@@ -530,8 +854,10 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
             }
 
             #[cfg(all(not(target_arch = "wasm32"), feature = "egui-support"))]
-            if let winit::event::Event::WindowEvent { event, .. } = &event {
-                let event_response = ctx.egui_glow.on_window_event(&g.window, event);
+            if let (winit::event::Event::WindowEvent { event, .. }, Some(egui_glow)) =
+                (&event, ctx.egui_glow.as_mut())
+            {
+                let event_response = egui_glow.on_window_event(&g.window, event);
                 if event_response.repaint {
                     g.window.request_redraw();
                 }
@@ -541,6 +867,299 @@ pub async unsafe fn run<App: Application + 'static>(init_info: WindowInitInfo) {
     .unwrap();
 }
 
+/// Drives `App` for a fixed number of frames in an offscreen pbuffer, no window or display needed.
+/// Useful on CI machines, e.g. with Mesa llvmpipe through EGL.
+#[cfg(not(target_arch = "wasm32"))]
+async unsafe fn run_headless<App: Application + 'static>(init_info: WindowInitInfo) {
+    let width = init_info.width;
+    let height = init_info.height;
+    let frames = init_info.frame_limit().unwrap_or(DEFAULT_FRAMES);
+    log::info!(
+        "Running {} headless at {}x{} for {} frames",
+        init_info.title,
+        width,
+        height,
+        frames
+    );
+
+    let context_api = glutin::context::ContextApi::OpenGl(Some(glutin::context::Version {
+        major: init_info.major,
+        minor: init_info.minor,
+    }));
+    let (gl, gl_surface, gl_context) = create_headless_context(&init_info, context_api);
+    let shader_version = shader_version(&gl, init_info.major, init_info.minor);
+
+    #[cfg(feature = "imgui-support")]
+    let gl = std::rc::Rc::new(gl);
+
+    #[cfg(not(feature = "imgui-support"))]
+    #[allow(clippy::arc_with_non_send_sync)]
+    let gl = Arc::new(gl);
+
+    #[cfg(feature = "imgui-support")]
+    let imgui_renderer = {
+        let mut r = easy_imgui_window::easy_imgui_renderer::Renderer::new(gl.clone()).unwrap();
+        r.set_background_color(None);
+        let size = easy_imgui_window::easy_imgui::Vector2::new(width as f32, height as f32);
+        r.set_size(size, 1.0);
+        r
+    };
+    let mut ctx = AppContext {
+        #[cfg(feature = "imgui-support")]
+        imgui_renderer,
+        #[cfg(feature = "imgui-support")]
+        imgui_status: easy_imgui_window::MainWindowStatus::default(),
+        gl_context: GLContext {
+            gl,
+            gl_surface: None,
+            context: None,
+        },
+        app_state: AppState::new(shader_version, width, height, 1.0, init_info.time_step),
+        #[cfg(feature = "egui-support")]
+        egui_glow: None,
+        gl_state: GlState::default(),
+    };
+
+    let mut app = App::new(&ctx).await;
+    app.resize(&ctx, width, height);
+
+    for _ in 0..frames {
+        update_app(&mut app, &mut ctx);
+
+        ctx.app_state.tick_render_time();
+        let gl = &ctx.gl_context.gl;
+        restore_gl_states(gl, &ctx.gl_state.states);
+        app.render(&ctx);
+        record_gl_states(gl, &mut ctx.gl_state.states);
+
+        use glutin::surface::GlSurface;
+        gl_surface.swap_buffers(&gl_context).unwrap();
+    }
+
+    if let Some(path) = &init_info.screenshot {
+        if let Err(e) = ctx.capture_frame(path, None) {
+            log::error!("Failed to save screenshot {:?}: {:?}", path, e);
+        }
+    }
+
+    log::info!("Exiting");
+    app.exit(&ctx);
+}
+
+/// The `#version` line for the requested GL version, or the one the context was created with
+/// if the driver gave an older one.
+#[cfg(not(target_arch = "wasm32"))]
+fn shader_version(gl: &Context, major: u8, minor: u8) -> &'static str {
+    let version = gl.version();
+    if version.is_embedded {
+        return match (version.major, version.minor) {
+            (3, 0) => "#version 300 es",
+            (3, 1) => "#version 310 es",
+            _ => "#version 320 es",
+        };
+    }
+    let requested = (major as u32, minor as u32);
+    match requested.min((version.major, version.minor)) {
+        (3, 0) => "#version 130",
+        (3, 1) => "#version 140",
+        (3, 2) => "#version 150 core",
+        (3, 3) => "#version 330 core",
+        (4, 0) => "#version 400 core",
+        (4, 1) => "#version 410 core",
+        (4, 2) => "#version 420 core",
+        (4, 3) => "#version 430 core",
+        (4, 4) => "#version 440 core",
+        (4, 5) => "#version 450 core",
+        (4, _) => "#version 460 core",
+        _ => "#version 330 core",
+    }
+}
+
+/// Whether tutorials can run headless here, i.e. there is an EGL device that can back a pbuffer.
+/// Lets tests skip instead of failing on machines without one.
+#[cfg(not(any(target_arch = "wasm32", target_os = "macos")))]
+pub fn headless_available() -> bool {
+    find_pbuffer_config(1).is_some()
+}
+
+#[cfg(target_os = "macos")]
+pub fn headless_available() -> bool {
+    false
+}
+
+/// A GL context current on this thread, backed by a small pbuffer, for tests that drive GL
+/// directly instead of through an `Application`.
+#[cfg(not(any(target_arch = "wasm32", target_os = "macos")))]
+pub struct HeadlessContext {
+    pub gl: SharedGl,
+    // dropped after `gl`
+    _surface: glutin::surface::Surface<glutin::surface::PbufferSurface>,
+    _context: glutin::context::PossiblyCurrentContext,
+}
+
+#[cfg(not(any(target_arch = "wasm32", target_os = "macos")))]
+impl HeadlessContext {
+    /// An OpenGL `major`.`minor` core context, `None` when `headless_available` is false.
+    pub fn new(major: u8, minor: u8) -> Option<Self> {
+        use glutin::context::{ContextApi, Version};
+        Self::with_api(ContextApi::OpenGl(Some(Version { major, minor })))
+    }
+
+    /// An OpenGL ES `major`.`minor` context, e.g. 3.0 to compile shaders by the rules WebGL 2 has.
+    pub fn new_es(major: u8, minor: u8) -> Option<Self> {
+        use glutin::context::{ContextApi, Version};
+        Self::with_api(ContextApi::Gles(Some(Version { major, minor })))
+    }
+
+    fn with_api(context_api: glutin::context::ContextApi) -> Option<Self> {
+        if !headless_available() {
+            return None;
+        }
+        let init_info = WindowInitInfo::builder().width(16).height(16).build();
+        let (gl, surface, context) = unsafe { create_headless_context(&init_info, context_api) };
+
+        #[cfg(feature = "imgui-support")]
+        let gl = std::rc::Rc::new(gl);
+
+        #[cfg(not(feature = "imgui-support"))]
+        #[allow(clippy::arc_with_non_send_sync)]
+        let gl = Arc::new(gl);
+
+        Some(Self {
+            gl,
+            _surface: surface,
+            _context: context,
+        })
+    }
+}
+
+/// The first EGL device with a pbuffer config for `num_samples`, and its config with the most samples.
+#[cfg(not(any(target_arch = "wasm32", target_os = "macos")))]
+fn find_pbuffer_config(
+    num_samples: u8,
+) -> Option<(
+    glutin::api::egl::display::Display,
+    glutin::api::egl::config::Config,
+)> {
+    use glutin::{
+        api::egl::{device::Device, display::Display},
+        config::{ConfigSurfaceTypes, ConfigTemplateBuilder, GlConfig},
+        display::GlDisplay,
+    };
+
+    let template = ConfigTemplateBuilder::new()
+        .with_surface_type(ConfigSurfaceTypes::PBUFFER)
+        .with_depth_size(24)
+        .with_stencil_size(8)
+        .with_multisampling(num_samples)
+        .build();
+
+    Device::query_devices().ok()?.find_map(|device| {
+        let display = unsafe { Display::with_device(&device, None) }.ok()?;
+        let config = unsafe { display.find_configs(template.clone()) }
+            .ok()?
+            .reduce(|accum, config| {
+                if config.num_samples() > accum.num_samples() {
+                    config
+                } else {
+                    accum
+                }
+            })?;
+        Some((display, config))
+    })
+}
+
+/// Creates a GL context on the first EGL device that can back a pbuffer surface.
+#[cfg(not(any(target_arch = "wasm32", target_os = "macos")))]
+unsafe fn create_headless_context(
+    init_info: &WindowInitInfo,
+    context_api: glutin::context::ContextApi,
+) -> (
+    glow::Context,
+    glutin::surface::Surface<glutin::surface::PbufferSurface>,
+    glutin::context::PossiblyCurrentContext,
+) {
+    use glutin::{
+        context::{ContextAttributesBuilder, NotCurrentGlContext},
+        display::{Display, GlDisplay},
+        surface::{PbufferSurface, SurfaceAttributesBuilder},
+    };
+    use std::num::NonZeroU32;
+
+    let (gl_display, gl_config) = find_pbuffer_config(init_info.num_samples)
+        .expect("Cannot find an EGL device with pbuffer support, headless mode requires EGL");
+    // wrap into the cross-platform types so the surface matches `GLContext`
+    let gl_display = Display::Egl(gl_display);
+    let gl_config = glutin::config::Config::Egl(gl_config);
+
+    let desktop = matches!(context_api, glutin::context::ContextApi::OpenGl(_));
+    #[allow(unused_mut)]
+    let mut context_attributes = ContextAttributesBuilder::new().with_context_api(context_api);
+    #[cfg(debug_assertions)]
+    {
+        context_attributes = context_attributes.with_debug(true);
+    }
+    let context_attributes = context_attributes.build(None);
+
+    let not_current_gl_context = unsafe {
+        gl_display
+            .create_context(&gl_config, &context_attributes)
+            .expect("Cannot create GL context")
+    };
+
+    let attrs = SurfaceAttributesBuilder::<PbufferSurface>::new().build(
+        NonZeroU32::new(init_info.width).expect("Width must be non-zero"),
+        NonZeroU32::new(init_info.height).expect("Height must be non-zero"),
+    );
+    let gl_surface = unsafe {
+        gl_display
+            .create_pbuffer_surface(&gl_config, &attrs)
+            .expect("Cannot create pbuffer surface")
+    };
+
+    let gl_context = not_current_gl_context.make_current(&gl_surface).unwrap();
+
+    #[allow(unused_mut)]
+    let mut gl =
+        unsafe { glow::Context::from_loader_function_cstr(|s| gl_display.get_proc_address(s)) };
+
+    #[cfg(debug_assertions)]
+    {
+        set_debug_callback(&mut gl);
+    }
+    unsafe { crate::program_cache::load_functions(&gl, |s| gl_display.get_proc_address(s)) };
+    // like windows, OpenGL ES 3 is always seamless and doesn't know the enum
+    if desktop {
+        unsafe { gl.enable(glow::TEXTURE_CUBE_MAP_SEAMLESS) };
+    }
+
+    (gl, gl_surface, gl_context)
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn create_headless_context(
+    _init_info: &WindowInitInfo,
+    _context_api: glutin::context::ContextApi,
+) -> (
+    glow::Context,
+    glutin::surface::Surface<glutin::surface::PbufferSurface>,
+    glutin::context::PossiblyCurrentContext,
+) {
+    panic!("Headless mode requires EGL, which is not available on macOS");
+}
+
+unsafe fn update_app<App: Application>(app: &mut App, ctx: &mut AppContext) {
+    let now = ctx.app_state.now();
+    if now == ctx.app_state.last_update_time {
+        // a `FixedStepClock` only moves once per frame, there is no time to update for
+        return;
+    }
+    ctx.app_state.update_delta_time =
+        (now - ctx.app_state.last_update_time).num_milliseconds() as f32 / 1000.0;
+    ctx.app_state.last_update_time = now;
+    app.update(ctx.app_state.update_delta_time);
+}
+
 #[cfg(feature = "imgui-support")]
 pub struct EasyImGuiFacade<'a, A>(&'a mut A);
 
@@ -0,0 +1,88 @@
+//! Runs a compute shader on a headless OpenGL 4.3 context and reads its storage buffer back.
+//!
+//! Skipped without an EGL device or when the driver has no compute shaders, Mesa llvmpipe has both.
+
+use glow::HasContext;
+use lib_learn_opengl_rs::{HeadlessContext, MyShader, StorageBuffer};
+use std::panic::AssertUnwindSafe;
+
+const DOUBLE: &str = r#"#version 430 core
+layout(local_size_x = 64) in;
+
+layout(std430, binding = 0) buffer Values {
+    float values[];
+};
+
+uniform uint count;
+
+void main() {
+    uint index = gl_GlobalInvocationID.x;
+    if (index >= count) {
+        return;
+    }
+    values[index] = values[index] * 2.0 + 1.0;
+}
+"#;
+
+fn compute_context() -> Option<HeadlessContext> {
+    let Some(context) = HeadlessContext::new(4, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return None;
+    };
+    if !MyShader::supports_compute(&context.gl) {
+        println!("skipped: the driver has no compute shaders");
+        return None;
+    }
+    Some(context)
+}
+
+#[test]
+fn dispatch_for_covers_a_storage_buffer() {
+    let Some(context) = compute_context() else {
+        return;
+    };
+    let gl = &context.gl;
+    // not a multiple of the local size, the last group skips the rest
+    let input: Vec<f32> = (0..100).map(|i| i as f32).collect();
+    let buffer = StorageBuffer::new(gl, 0, &input);
+    let shader = MyShader::new_compute_from_source(gl, DOUBLE).unwrap();
+    shader.use_shader(gl);
+    shader.set_uint(gl, "count", buffer.len() as u32);
+    shader.dispatch_for(gl, [buffer.len() as u32, 1, 1], [64, 1, 1]);
+    unsafe { gl.memory_barrier(glow::BUFFER_UPDATE_BARRIER_BIT) };
+
+    let output = buffer.read(gl);
+    let expected: Vec<f32> = input.iter().map(|value| value * 2.0 + 1.0).collect();
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn set_replaces_and_resizes_the_buffer() {
+    let Some(context) = compute_context() else {
+        return;
+    };
+    let gl = &context.gl;
+    let mut buffer = StorageBuffer::new(gl, 0, &[1.0f32; 4]);
+    buffer.set(gl, &[3.0, 4.0]);
+    assert_eq!(buffer.len(), 2);
+    let shader = MyShader::new_compute_from_source(gl, DOUBLE).unwrap();
+    shader.use_shader(gl);
+    shader.set_uint(gl, "count", 2);
+    shader.dispatch(gl, [1, 1, 1]);
+    unsafe { gl.memory_barrier(glow::BUFFER_UPDATE_BARRIER_BIT) };
+    assert_eq!(buffer.read(gl), [7.0, 9.0]);
+}
+
+#[test]
+fn dispatch_for_rejects_an_empty_local_size() {
+    let Some(context) = compute_context() else {
+        return;
+    };
+    let gl = &context.gl;
+    let shader = MyShader::new_compute_from_source(gl, DOUBLE).unwrap();
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        shader.dispatch_for(gl, [100, 1, 1], [64, 0, 1]);
+    }));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("local_size"), "{}", message);
+}
@@ -0,0 +1,172 @@
+//! Faces `CubemapTexture` cuts out of crosses and resamples from panoramas, in the order of
+//! `TEXTURE_CUBE_MAP_POSITIVE_X + i`, no GL context needed.
+
+use image::{DynamicImage, GenericImageView, Rgb, Rgb32FImage, RgbImage};
+use lib_learn_opengl_rs::CubemapTexture;
+
+const RIGHT: usize = 0;
+const LEFT: usize = 1;
+const TOP: usize = 2;
+const BOTTOM: usize = 3;
+const FRONT: usize = 4;
+const BACK: usize = 5;
+
+/// Distinct colors of the faces.
+const COLORS: [[u8; 3]; 6] = [
+    [255, 0, 0],
+    [0, 255, 0],
+    [0, 0, 255],
+    [255, 255, 0],
+    [0, 255, 255],
+    [255, 0, 255],
+];
+
+/// A cross of faces `size` wide, each the color of its face, with `cells` giving the
+/// `(column, row)` of every face. The top left texel of every face is white.
+fn cross(columns: u32, rows: u32, size: u32, cells: [(u32, u32); 6]) -> DynamicImage {
+    let img = RgbImage::from_fn(columns * size, rows * size, |x, y| {
+        let cell = (x / size, y / size);
+        match cells.iter().position(|&c| c == cell) {
+            Some(_) if (x % size, y % size) == (0, 0) => Rgb([255, 255, 255]),
+            Some(face) => Rgb(COLORS[face]),
+            None => Rgb([0, 0, 0]),
+        }
+    });
+    DynamicImage::ImageRgb8(img)
+}
+
+fn texel(face: &DynamicImage, x: u32, y: u32) -> [u8; 3] {
+    face.to_rgb8().get_pixel(x, y).0
+}
+
+#[test]
+fn horizontal_cross_faces_land_on_their_slots() {
+    let mut cells = [(0, 0); 6];
+    cells[TOP] = (1, 0);
+    cells[LEFT] = (0, 1);
+    cells[FRONT] = (1, 1);
+    cells[RIGHT] = (2, 1);
+    cells[BACK] = (3, 1);
+    cells[BOTTOM] = (1, 2);
+    let faces = CubemapTexture::cross_faces(&cross(4, 3, 4, cells)).unwrap();
+
+    for (i, face) in faces.iter().enumerate() {
+        assert_eq!(face.dimensions(), (4, 4));
+        assert_eq!(texel(face, 0, 0), [255, 255, 255], "face {}", i);
+        assert_eq!(texel(face, 3, 3), COLORS[i], "face {}", i);
+        assert_eq!(texel(face, 1, 2), COLORS[i], "face {}", i);
+    }
+}
+
+#[test]
+fn vertical_cross_turns_the_back_around() {
+    let mut cells = [(0, 0); 6];
+    cells[TOP] = (1, 0);
+    cells[LEFT] = (0, 1);
+    cells[FRONT] = (1, 1);
+    cells[RIGHT] = (2, 1);
+    cells[BOTTOM] = (1, 2);
+    cells[BACK] = (1, 3);
+    let faces = CubemapTexture::cross_faces(&cross(3, 4, 4, cells)).unwrap();
+
+    for (i, face) in faces.iter().enumerate() {
+        assert_eq!(face.dimensions(), (4, 4));
+        // the back is stored upside down below the bottom
+        let marked = if i == BACK { (3, 3) } else { (0, 0) };
+        assert_eq!(
+            texel(face, marked.0, marked.1),
+            [255, 255, 255],
+            "face {}",
+            i
+        );
+        assert_eq!(texel(face, 1, 2), COLORS[i], "face {}", i);
+    }
+}
+
+#[test]
+fn crosses_have_to_be_four_by_three() {
+    let img = DynamicImage::ImageRgb8(RgbImage::new(8, 8));
+    assert!(CubemapTexture::cross_faces(&img).is_err());
+}
+
+/// A float panorama holding the texture coordinates of every texel center in red and green,
+/// which bilinear sampling reproduces exactly away from the edges.
+fn coordinate_panorama(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgb32F(Rgb32FImage::from_fn(width, height, |x, y| {
+        Rgb([
+            (x as f32 + 0.5) / width as f32,
+            (y as f32 + 0.5) / height as f32,
+            0.0,
+        ])
+    }))
+}
+
+/// The texture coordinates the center of `face` sampled, the face size is odd so a texel sits there.
+fn center_uv(face: &DynamicImage) -> (f32, f32) {
+    let Some(face) = face.as_rgb32f() else {
+        panic!("float panoramas give float faces");
+    };
+    let center = face.width() / 2;
+    let [u, v, _] = face.get_pixel(center, center).0;
+    (u, v)
+}
+
+fn assert_near(actual: f32, expected: f32, what: &str) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "{}: {} != {}",
+        what,
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn equirectangular_face_centers_look_along_the_axes() {
+    let (width, height) = (64, 32);
+    let faces = CubemapTexture::equirectangular_faces(&coordinate_panorama(width, height), 5);
+    assert!(faces.iter().all(|face| face.dimensions() == (5, 5)));
+
+    // +X is in the middle of the panorama, on the horizon
+    let (u, v) = center_uv(&faces[RIGHT]);
+    assert_near(u, 0.5, "+X u");
+    assert_near(v, 0.5, "+X v");
+    // -Z a quarter turn to the left of it, +Z to the right
+    let (u, v) = center_uv(&faces[BACK]);
+    assert_near(u, 0.25, "-Z u");
+    assert_near(v, 0.5, "-Z v");
+    let (u, _) = center_uv(&faces[FRONT]);
+    assert_near(u, 0.75, "+Z u");
+    // +Y looks at the top row, the sample is clamped to its texel centers
+    let (_, v) = center_uv(&faces[TOP]);
+    assert_near(v, 0.5 / height as f32, "+Y v");
+    let (_, v) = center_uv(&faces[BOTTOM]);
+    assert_near(v, 1.0 - 0.5 / height as f32, "-Y v");
+}
+
+#[test]
+fn equirectangular_faces_are_upright() {
+    let faces = CubemapTexture::equirectangular_faces(&coordinate_panorama(64, 32), 5);
+    for i in [RIGHT, LEFT, FRONT, BACK] {
+        let Some(face) = faces[i].as_rgb32f() else {
+            panic!("float panoramas give float faces");
+        };
+        // rows further down the side faces sample further down the panorama
+        let top = face.get_pixel(2, 0).0[1];
+        let bottom = face.get_pixel(2, 4).0[1];
+        assert!(top < 0.5 && bottom > 0.5, "face {}: {} {}", i, top, bottom);
+    }
+    // s of +X runs along -Z, which is further left in the panorama
+    let face = faces[RIGHT].as_rgb32f().unwrap();
+    assert!(face.get_pixel(4, 2).0[0] < face.get_pixel(0, 2).0[0]);
+}
+
+#[test]
+fn eight_bit_panoramas_give_eight_bit_faces() {
+    let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 4, Rgb([10, 20, 30])));
+    let faces = CubemapTexture::equirectangular_faces(&img, 3);
+    for face in &faces {
+        assert!(face.as_rgb8().is_some());
+        assert_eq!(texel(face, 1, 1), [10, 20, 30]);
+    }
+}
@@ -0,0 +1,525 @@
+//! Runs every shader in `src/*/shaders` and the library's `src/shaders` through naga's GLSL
+//! frontend, each variant listed in `VARIANTS` on its own, once as written
+//! for `#version 330 core` and once rewritten by `modify_shader_to_support_webgl` to `#version 300 es`,
+//! and looks for constructs WebGL 2 doesn't have, so they show up before anyone opens a browser.
+//!
+//! naga only reads desktop GLSL, so the ES rewrite is also compiled by the driver on a headless
+//! OpenGL ES 3.0 context, which holds it to the ES 3.00 rules, e.g. no implicit int to float
+//! conversions and precision for floats in fragment shaders. That part is skipped without an EGL device.
+//!
+//! Shaders that are desktop only on purpose are listed in `DESKTOP_ONLY`, a tutorial using them
+//! needs a `_web` variant or an `unsupported_reason` for wasm.
+
+use glow::HasContext;
+use lib_learn_opengl_rs::{HeadlessContext, MyShader, ShaderType};
+use naga::front::glsl::{Frontend, Options};
+use naga::ShaderStage;
+use std::path::{Path, PathBuf};
+
+const DESKTOP_ONLY: [(&str, &str); 8] = [
+    (
+        "_4_advanced_opengl/_9_1_geometry_shader.vs",
+        "feeds a geometry shader",
+    ),
+    (
+        "_4_advanced_opengl/_9_1_geometry_shader.gs",
+        "geometry shader",
+    ),
+    (
+        "_4_advanced_opengl/_9_2_geometry_shader.vs",
+        "feeds a geometry shader",
+    ),
+    (
+        "_4_advanced_opengl/_9_2_geometry_shader.gs",
+        "geometry shader",
+    ),
+    (
+        "_4_advanced_opengl/_9_3_normal_visualization.vs",
+        "feeds a geometry shader",
+    ),
+    (
+        "_4_advanced_opengl/_9_3_normal_visualization.gs",
+        "geometry shader",
+    ),
+    (
+        "_5_advanced_lighting/_1_1_advanced_lighting.vs",
+        "web uses _1_1_advanced_lighting_web.vs",
+    ),
+    (
+        "_5_advanced_lighting/_1_1_advanced_lighting.fs",
+        "web uses _1_1_advanced_lighting_web.fs",
+    ),
+];
+
+/// Define sets a shader is compiled with, e.g. by `ShaderVariants`, each is checked like a shader
+/// of its own. Shaders not listed are only checked without defines.
+const NO_DEFINES: &[&[&str]] = &[&[]];
+const VARIANTS: [(&str, &[&[&str]]); 1] = [(
+    "shaders/post_process.fs",
+    &[&[], &["INVERSION"], &["GRAYSCALE"], &["KERNEL"]],
+)];
+
+#[test]
+fn shaders_parse_and_are_webgl_compatible() {
+    let es = HeadlessContext::new_es(3, 0);
+    if es.is_none() {
+        println!("ES 3.00 compiles skipped: no EGL device with pbuffer support");
+    }
+    let mut failures = Vec::new();
+    let mut desktop_only = Vec::new();
+    for shader in shader_files() {
+        let stage = match shader.path.extension().and_then(|e| e.to_str()) {
+            Some("vs") => ShaderType::Vertex,
+            Some("fs") => ShaderType::Fragment,
+            Some("gs") => ShaderType::Geometry,
+            // included by other shaders, checked as part of them
+            Some("glsl") => continue,
+            _ => {
+                failures.push(format!("{}: unknown shader stage", shader.id));
+                continue;
+            }
+        };
+        let source = match shader.load() {
+            Ok(source) => source,
+            Err(e) => {
+                failures.push(format!("{}: cannot load: {}", shader.id, e));
+                continue;
+            }
+        };
+        let variants = VARIANTS
+            .iter()
+            .find(|(listed, _)| *listed == shader.id)
+            .map_or(NO_DEFINES, |(_, variants)| variants);
+        for defines in variants {
+            let (id, source) = if defines.is_empty() {
+                (shader.id.clone(), source.clone())
+            } else {
+                (
+                    format!("{} with {}", shader.id, defines.join(", ")),
+                    with_defines(&source, defines),
+                )
+            };
+            check(
+                stage,
+                &id,
+                &source,
+                es.as_ref(),
+                &mut failures,
+                &mut desktop_only,
+            );
+        }
+    }
+    for line in desktop_only {
+        println!("desktop only {}", line);
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Validates one shader or variant of one, adding what's wrong to `failures`
+/// and the reasons of shaders in `DESKTOP_ONLY` to `desktop_only`.
+fn check(
+    stage: ShaderType,
+    id: &str,
+    source: &str,
+    es: Option<&HeadlessContext>,
+    failures: &mut Vec<String>,
+    desktop_only: &mut Vec<String>,
+) {
+    let webgl_source =
+        MyShader::modify_shader_to_support_webgl(source, Some("#version 300 es"), stage);
+    for (version, source) in [("330 core", source), ("300 es", webgl_source.as_str())] {
+        if let Err(e) = validate(stage, source) {
+            failures.push(format!("{} as {}: {}", id, version, e));
+        }
+    }
+
+    let mut incompatibilities = webgl_incompatibilities(stage, &webgl_source);
+    if let Some(es) = es.filter(|_| stage != ShaderType::Geometry) {
+        if let Err(info_log) = compile(&es.gl, stage, &webgl_source) {
+            incompatibilities.push(format!("rejected by GLSL ES 3.00: {}", info_log.trim()));
+        }
+    }
+    let listed = DESKTOP_ONLY.iter().find(|(listed, _)| *listed == id);
+    match (incompatibilities.is_empty(), listed) {
+        (false, None) => failures.push(format!(
+            "{}: not supported by WebGL 2: {}; add a _web variant and list it in DESKTOP_ONLY",
+            id,
+            incompatibilities.join(", ")
+        )),
+        (true, Some(_)) => failures.push(format!(
+            "{}: listed in DESKTOP_ONLY but WebGL 2 compatible, remove it from the list",
+            id
+        )),
+        (false, Some((_, reason))) => desktop_only.push(format!("{}: {}", id, reason)),
+        (true, None) => {}
+    }
+}
+
+/// `source` with `#define`s of `defines` after its `#version` line, like `ShaderBuilder::define`.
+fn with_defines(source: &str, defines: &[&str]) -> String {
+    let defines: String = defines
+        .iter()
+        .map(|name| format!("#define {} 1\n", name))
+        .collect();
+    match source.split_once('\n') {
+        Some((version, rest)) if version.starts_with("#version") => {
+            format!("{}\n{}{}", version, defines, rest)
+        }
+        _ => format!("{}{}", defines, source),
+    }
+}
+
+/// A file of `src/*/shaders` or `src/shaders`.
+struct ShaderFile {
+    /// `chapter/file` of tutorial shaders, `shaders/file` of the library's
+    id: String,
+    path: PathBuf,
+    /// the resource path tutorials load it from, `None` for the library's embedded shaders
+    resource: Option<String>,
+}
+
+impl ShaderFile {
+    /// The source with `#include`s expanded like the tutorials load it.
+    fn load(&self) -> Result<String, String> {
+        match &self.resource {
+            Some(resource) => pollster::block_on(MyShader::load_source(resource)).map(|s| s.source),
+            None => std::fs::read_to_string(&self.path).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Every file in `src/*/shaders` and `src/shaders`, sorted by id.
+fn shader_files() -> Vec<ShaderFile> {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut files = Vec::new();
+    for path in read_dir(&src.join("shaders")) {
+        files.push(ShaderFile {
+            id: format!("shaders/{}", file_name(&path)),
+            path,
+            resource: None,
+        });
+    }
+    for chapter in read_dir(&src) {
+        let shaders = chapter.join("shaders");
+        if !shaders.is_dir() {
+            continue;
+        }
+        let chapter = file_name(&chapter);
+        for path in read_dir(&shaders) {
+            let id = format!("{}/{}", chapter, file_name(&path));
+            files.push(ShaderFile {
+                resource: Some(format!("shaders/{}", id)),
+                id,
+                path,
+            });
+        }
+    }
+    files.sort_by(|a, b| a.id.cmp(&b.id));
+    files
+}
+
+fn read_dir(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_str().unwrap().to_string()
+}
+
+/// Compiles a vertex or fragment shader with the driver, the info log on failure.
+fn compile(gl: &glow::Context, stage: ShaderType, source: &str) -> Result<(), String> {
+    let shader_type = match stage {
+        ShaderType::Vertex => glow::VERTEX_SHADER,
+        _ => glow::FRAGMENT_SHADER,
+    };
+    unsafe {
+        let shader = gl.create_shader(shader_type)?;
+        gl.shader_source(shader, source);
+        gl.compile_shader(shader);
+        let result = if gl.get_shader_compile_status(shader) {
+            Ok(())
+        } else {
+            Err(gl.get_shader_info_log(shader))
+        };
+        gl.delete_shader(shader);
+        result
+    }
+}
+
+/// Parses `source` with naga, which has no geometry stage.
+fn validate(stage: ShaderType, source: &str) -> Result<(), String> {
+    let stage = match stage {
+        ShaderType::Vertex => ShaderStage::Vertex,
+        ShaderType::Fragment => ShaderStage::Fragment,
+        _ => return Ok(()),
+    };
+    let source = for_naga(source);
+    // naga's validator checks WGSL rules on top, e.g. no `bool` uniforms, so only the frontend runs,
+    // which resolves names and types of every expression
+    Frontend::default()
+        .parse(&Options::from(stage), &source)
+        .map(|_| ())
+        .map_err(|e| e.emit_to_string(&source))
+}
+
+/// Rewrites OpenGL flavoured GLSL into the Vulkan flavoured GLSL 4.50 naga reads:
+/// uniforms need a binding and samplers are a separate texture and sampler,
+/// combined again by a macro with the sampler's name so uses stay as written.
+/// Samplers in structs become uniforms of their own, `material.diffuse` turns into `material_diffuse`.
+fn for_naga(source: &str) -> String {
+    let mut binding = 0;
+    let mut next_binding = || {
+        binding += 1;
+        binding - 1
+    };
+    let declare_sampler = |binding: &mut dyn FnMut() -> i32, sampler: &str, name: &str| {
+        let (texture, sampler_type) = match sampler {
+            "samplerCube" => ("textureCube", "sampler"),
+            "sampler2DShadow" => ("texture2D", "samplerShadow"),
+            _ => ("texture2D", "sampler"),
+        };
+        format!(
+            "layout(binding = {}) uniform {} {name}_texture;\n\
+             layout(binding = {}) uniform {} {name}_sampler;\n\
+             #define {name} {}({name}_texture, {name}_sampler)",
+            binding(),
+            texture,
+            binding(),
+            sampler_type,
+            sampler,
+        )
+    };
+
+    // sampler members by struct name
+    let mut struct_samplers: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    // `uniform.member` of samplers in structs
+    let mut flattened = Vec::new();
+    let mut in_struct = None;
+    let mut depth = 0;
+    let (mut in_location, mut out_location) = (0, 0);
+    let mut lines = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let code = line.split("//").next().unwrap();
+        let words: Vec<&str> = code
+            .split(|c: char| c.is_whitespace() || c == ';')
+            .filter(|word| !word.is_empty())
+            .collect();
+        let global = depth == 0;
+        depth += code.matches('{').count() as i32 - code.matches('}').count() as i32;
+        if i == 0 && line.starts_with("#version") {
+            lines.push("#version 450 core".to_string());
+        } else if words.first() == Some(&"struct") {
+            struct_samplers.push((words[1].trim_end_matches('{').to_string(), Vec::new()));
+            in_struct = Some(struct_samplers.len() - 1);
+            lines.push(line.to_string());
+        } else if let Some(index) = in_struct {
+            if depth == 0 {
+                in_struct = None;
+            }
+            match words.as_slice() {
+                [sampler, name] if sampler.starts_with("sampler") => struct_samplers[index]
+                    .1
+                    .push((sampler.to_string(), name.to_string())),
+                _ => lines.push(line.to_string()),
+            }
+        } else if global && words.first() == Some(&"uniform") && words.len() >= 3 {
+            let (ty, name) = (words[1], words[2]);
+            if ty.starts_with("sampler") {
+                lines.push(declare_sampler(&mut next_binding, ty, name));
+            } else {
+                if let Some((_, samplers)) = struct_samplers.iter().find(|(s, _)| s == ty) {
+                    for (sampler, member) in samplers {
+                        let uniform = format!("{}_{}", name, member);
+                        lines.push(declare_sampler(&mut next_binding, sampler, &uniform));
+                        flattened.push((format!("{}.{}", name, member), uniform));
+                    }
+                }
+                lines.push(format!("layout(binding = {}) {}", next_binding(), line));
+            }
+        } else if global
+            && matches!(words.first(), Some(&"in") | Some(&"out"))
+            && !code.contains(')')
+        {
+            // naga puts varyings without a location at 0, GLSL matches them by name
+            let location = if words[0] == "in" {
+                &mut in_location
+            } else {
+                &mut out_location
+            };
+            lines.push(format!("layout(location = {}) {}", location, line));
+            *location += 1;
+        } else if global && words.first() == Some(&"layout") && words.contains(&"uniform") {
+            // e.g. `layout (std140) uniform Matrices`
+            lines.push(line.replacen(')', &format!(", binding = {})", next_binding()), 1));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    let mut source = lines.join("\n");
+    for (member, uniform) in flattened {
+        source = replace_word(&source, &member, &uniform);
+    }
+    source
+}
+
+/// Replaces `from` where it isn't part of a longer identifier.
+fn replace_word(source: &str, from: &str, to: &str) -> String {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    let mut replaced = String::new();
+    let mut rest = source;
+    while let Some(at) = rest.find(from) {
+        let end = at + from.len();
+        replaced.push_str(&rest[..at]);
+        // what came before may already be in `replaced`
+        let whole_word =
+            !replaced.ends_with(is_identifier) && !rest[end..].starts_with(is_identifier);
+        replaced.push_str(if whole_word { to } else { from });
+        rest = &rest[end..];
+    }
+    replaced.push_str(rest);
+    replaced
+}
+
+/// What in the `#version 300 es` rewrite of a shader WebGL 2 doesn't support.
+fn webgl_incompatibilities(stage: ShaderType, source: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    if stage == ShaderType::Geometry {
+        found.push("geometry shaders".to_string());
+    }
+    let code: String = source
+        .lines()
+        .map(|line| line.split("//").next().unwrap())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace('{', " { ")
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .replace(',', " , ")
+        .replace('=', " = ");
+    let words: Vec<&str> = code.split_whitespace().collect();
+    // `uniform Block {` is fine, only `in`/`out` blocks need OpenGL ES 3.2
+    if words
+        .windows(3)
+        .any(|w| (w[0] == "in" || w[0] == "out") && w[2] == "{")
+    {
+        found.push("in/out interface blocks".to_string());
+    }
+    let mut in_layout = false;
+    for word in &words {
+        match *word {
+            "layout" => in_layout = true,
+            ")" => in_layout = false,
+            "binding" if in_layout => {
+                found.push("layout bindings".to_string());
+                break;
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+#[test]
+fn es_compile_catches_what_naga_accepts() {
+    let Some(es) = HeadlessContext::new_es(3, 0) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let implicit_conversion = "#version 330 core\nout vec4 FragColor;\nvoid main() {\n    float ratio = 1 / 2.0;\n    FragColor = vec4(ratio);\n}\n";
+    assert!(validate(ShaderType::Fragment, implicit_conversion).is_ok());
+    let webgl_source = MyShader::modify_shader_to_support_webgl(
+        implicit_conversion,
+        Some("#version 300 es"),
+        ShaderType::Fragment,
+    );
+    assert!(compile(&es.gl, ShaderType::Fragment, &webgl_source).is_err());
+    // the int literal was the only problem
+    let fixed = webgl_source.replace("1 / 2.0", "1.0 / 2.0");
+    compile(&es.gl, ShaderType::Fragment, &fixed).unwrap();
+
+    // no precision statement is added to vertex shaders, where highp is the default
+    let vertex = "#version 330 core\nlayout (location = 0) in vec3 aPos;\nvoid main() {\n    gl_Position = vec4(aPos, 1.0);\n}\n";
+    let webgl_source = MyShader::modify_shader_to_support_webgl(
+        vertex,
+        Some("#version 300 es"),
+        ShaderType::Vertex,
+    );
+    compile(&es.gl, ShaderType::Vertex, &webgl_source).unwrap();
+}
+
+#[test]
+fn replace_word_skips_longer_identifiers() {
+    assert_eq!(
+        replace_word("a.b + a.bc + xa.b + a.b_2 + (a.b)", "a.b", "a_b"),
+        "a_b + a.bc + xa.b + a.b_2 + (a_b)"
+    );
+    assert_eq!(replace_word("", "a", "b"), "");
+    assert_eq!(replace_word("aaa", "a", "b"), "aaa");
+}
+
+#[test]
+fn for_naga_replaces_the_version_and_binds_uniforms() {
+    let source =
+        "#version 300 es\nprecision mediump float;\nuniform mat4 model;\nuniform vec3 color;\n\
+                  void main() {\n    float uniform_like = 1.0;\n}\n";
+    let rewritten = for_naga(source);
+    let lines: Vec<&str> = rewritten.lines().collect();
+    assert_eq!(lines[0], "#version 450 core");
+    assert_eq!(lines[1], "precision mediump float;");
+    assert_eq!(lines[2], "layout(binding = 0) uniform mat4 model;");
+    assert_eq!(lines[3], "layout(binding = 1) uniform vec3 color;");
+    // only the first line is a version to replace, locals stay as written
+    assert_eq!(lines[5], "    float uniform_like = 1.0;");
+    assert!(validate(ShaderType::Fragment, source).is_ok());
+}
+
+#[test]
+fn for_naga_splits_samplers_into_texture_and_sampler() {
+    let rewritten = for_naga(
+        "#version 330 core\nuniform sampler2D diffuse;\nuniform samplerCube skybox;\n\
+         uniform sampler2DShadow shadowMap;\n",
+    );
+    assert!(rewritten.contains(
+        "layout(binding = 0) uniform texture2D diffuse_texture;\n\
+         layout(binding = 1) uniform sampler diffuse_sampler;\n\
+         #define diffuse sampler2D(diffuse_texture, diffuse_sampler)"
+    ));
+    assert!(rewritten.contains("uniform textureCube skybox_texture;"));
+    assert!(rewritten.contains("#define skybox samplerCube(skybox_texture, skybox_sampler)"));
+    assert!(rewritten.contains("uniform samplerShadow shadowMap_sampler;"));
+}
+
+#[test]
+fn for_naga_flattens_samplers_in_structs() {
+    let source = "#version 330 core\nout vec4 FragColor;\nstruct Material {\n    sampler2D diffuse;\n    float shininess;\n};\n\
+                  uniform Material material;\nin vec2 TexCoords;\n\
+                  void main() {\n    FragColor = texture(material.diffuse, TexCoords) * material.shininess;\n}\n";
+    let rewritten = for_naga(source);
+    assert!(!rewritten.contains("    sampler2D diffuse;"));
+    assert!(rewritten.contains("    float shininess;"));
+    assert!(rewritten.contains("#define material_diffuse sampler2D("));
+    assert!(rewritten.contains("layout(binding = 2) uniform Material material;"));
+    assert!(rewritten.contains("texture(material_diffuse, TexCoords) * material.shininess"));
+    validate(ShaderType::Fragment, source).unwrap();
+}
+
+#[test]
+fn for_naga_numbers_varyings_and_binds_blocks() {
+    let rewritten = for_naga(
+        "#version 330 core\nlayout (location = 0) in vec3 aPos;\nin vec2 uv;\nin vec3 normal;\nout vec3 color;\n\
+         layout (std140) uniform Matrices {\n    mat4 view;\n};\nvoid helper(in vec3 value, out vec3 result) {}\n",
+    );
+    let lines: Vec<&str> = rewritten.lines().collect();
+    // only varyings without a layout get a location, counted per direction
+    assert_eq!(lines[1], "layout (location = 0) in vec3 aPos;");
+    assert_eq!(lines[2], "layout(location = 0) in vec2 uv;");
+    assert_eq!(lines[3], "layout(location = 1) in vec3 normal;");
+    assert_eq!(lines[4], "layout(location = 0) out vec3 color;");
+    assert_eq!(lines[5], "layout (std140, binding = 0) uniform Matrices {");
+    assert_eq!(lines[6], "    mat4 view;");
+    assert_eq!(lines[8], "void helper(in vec3 value, out vec3 result) {}");
+}
@@ -0,0 +1,153 @@
+//! Renders every tutorial headlessly on a fixed-step clock and compares the last frame with `tests/golden/<id>.png`.
+//!
+//! Needs an EGL device, Mesa llvmpipe is enough, and is skipped without one. Run with `UPDATE_GOLDEN=1` to write the
+//! reference images instead of comparing, e.g. after an intended visual change.
+//! On mismatch the actual frame and a diff image are written to `target/tmp/golden`.
+
+use image::{Rgba, RgbaImage};
+use lib_learn_opengl_rs::{
+    headless_available, run_tutorial, set_launch_options, LaunchOptions, Platform, TUTORIALS,
+};
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+const FRAMES: u32 = 3;
+const TIME_STEP: Duration = Duration::from_millis(100);
+/// max difference per channel before a pixel counts as mismatched
+const TOLERANCE: u8 = 8;
+/// share of mismatched pixels allowed, rasterizers disagree on some edges
+const MAX_MISMATCHED_RATIO: f64 = 0.01;
+
+const SKIPPED: [(&str, &str); 2] = [
+    ("3_1_1", "objects/backpack/backpack.obj is not checked in"),
+    ("4_9_3", "objects/backpack/backpack.obj is not checked in"),
+];
+
+#[test]
+fn tutorials_match_golden_images() {
+    if !headless_available() {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    }
+    let golden_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    if update {
+        std::fs::create_dir_all(&golden_dir).unwrap();
+    }
+
+    let mut failures = Vec::new();
+    for tutorial in &TUTORIALS {
+        let id = tutorial.id;
+        if let Some(reason) = tutorial.unsupported_reason(Platform::Headless) {
+            println!("{}: skipped, {}", id, reason);
+            continue;
+        }
+        if let Some((_, reason)) = SKIPPED.iter().find(|(skipped, _)| *skipped == id) {
+            println!("{}: skipped, {}", id, reason);
+            continue;
+        }
+
+        let actual_path = out_dir.join(format!("{}.png", id));
+        let actual = match render(id, &actual_path) {
+            Ok(actual) => actual,
+            Err(e) => {
+                failures.push(format!("{}: {}", id, e));
+                continue;
+            }
+        };
+
+        let golden_path = golden_dir.join(format!("{}.png", id));
+        if update {
+            actual.save(&golden_path).unwrap();
+            println!("{}: updated {:?}", id, golden_path);
+            continue;
+        }
+        let Ok(golden) = image::open(&golden_path) else {
+            failures.push(format!("{}: missing reference {:?}", id, golden_path));
+            continue;
+        };
+
+        let (mismatched, diff) = compare(&golden.to_rgba8(), &actual);
+        let ratio = mismatched as f64 / (WIDTH * HEIGHT) as f64;
+        if ratio > MAX_MISMATCHED_RATIO {
+            let diff_path = out_dir.join(format!("{}.diff.png", id));
+            diff.save(&diff_path).unwrap();
+            failures.push(format!(
+                "{}: {:.2}% pixels differ, see {:?} and {:?}",
+                id,
+                ratio * 100.0,
+                actual_path,
+                diff_path
+            ));
+        } else {
+            println!("{}: ok", id);
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} tutorials differ from their golden images:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+fn render(id: &str, path: &PathBuf) -> Result<RgbaImage, String> {
+    let _ = std::fs::remove_file(path);
+    set_launch_options(LaunchOptions {
+        headless: true,
+        frames: Some(FRAMES),
+        screenshot: Some(path.clone()),
+        width: Some(WIDTH),
+        height: Some(HEIGHT),
+        time_step: Some(TIME_STEP),
+        seed: Some(0),
+        ..Default::default()
+    });
+    std::panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+        pollster::block_on(run_tutorial(id.to_string()));
+    }))
+    .map_err(|e| {
+        let message = e
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| e.downcast_ref::<&str>().copied())
+            .unwrap_or("unknown panic");
+        format!("panicked: {}", message)
+    })?;
+    image::open(path)
+        .map(|img| img.to_rgba8())
+        .map_err(|e| format!("no frame captured: {}", e))
+}
+
+/// Returns the number of mismatched pixels and an image marking them in red over a faded frame.
+fn compare(golden: &RgbaImage, actual: &RgbaImage) -> (usize, RgbaImage) {
+    if golden.dimensions() != actual.dimensions() {
+        let (width, height) = actual.dimensions();
+        return (
+            (width * height) as usize,
+            RgbaImage::from_pixel(width, height, Rgba([255, 0, 0, 255])),
+        );
+    }
+
+    let mut mismatched = 0;
+    let diff = RgbaImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let (g, a) = (golden.get_pixel(x, y), actual.get_pixel(x, y));
+        if g.0
+            .iter()
+            .zip(a.0.iter())
+            .any(|(g, a)| g.abs_diff(*a) > TOLERANCE)
+        {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([a[0] / 4, a[1] / 4, a[2] / 4, 255])
+        }
+    });
+    (mismatched, diff)
+}
@@ -0,0 +1,148 @@
+//! Draws a texture through `PostProcessStack` on a headless context, with built-in effects and a
+//! shader of the test's own, and reads the output target back.
+//!
+//! Skipped without an EGL device.
+
+use glow::HasContext;
+use lib_learn_opengl_rs::{
+    ColorFormat, Effect, HeadlessContext, MyShader, PostProcessStack, RenderTarget,
+};
+
+const SIZE: u32 = 4;
+
+/// Swaps red and blue and scales the result by `strength`.
+const SWIZZLE: &str = r#"#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+uniform sampler2D screenTexture;
+uniform float strength;
+
+void main()
+{
+    FragColor = vec4(texture(screenTexture, TexCoords).bgr * strength, 1.0);
+}
+"#;
+
+/// A texture of `SIZE` x `SIZE` pixels of `color`.
+fn solid_texture(gl: &glow::Context, color: [u8; 4]) -> glow::Texture {
+    let pixels = color.repeat((SIZE * SIZE) as usize);
+    unsafe {
+        let texture = gl.create_texture().unwrap();
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            SIZE as i32,
+            SIZE as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(&pixels),
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MIN_FILTER,
+            glow::NEAREST as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MAG_FILTER,
+            glow::NEAREST as i32,
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        texture
+    }
+}
+
+/// The pixels of the target `PostProcessStack::apply` left bound.
+fn read_output(gl: &glow::Context) -> Vec<[u8; 4]> {
+    let mut pixels = vec![0u8; (SIZE * SIZE * 4) as usize];
+    unsafe {
+        gl.read_pixels(
+            0,
+            0,
+            SIZE as i32,
+            SIZE as i32,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelPackData::Slice(&mut pixels),
+        );
+    }
+    pixels
+        .chunks_exact(4)
+        .map(|pixel| pixel.try_into().unwrap())
+        .collect()
+}
+
+fn all_near(pixels: &[[u8; 4]], expected: [u8; 4]) -> bool {
+    pixels.iter().all(|pixel| {
+        pixel
+            .iter()
+            .zip(expected)
+            .all(|(&value, expected)| value.abs_diff(expected) <= 1)
+    })
+}
+
+#[test]
+fn built_in_effects_chain() {
+    let Some(context) = HeadlessContext::new(3, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let gl = &context.gl;
+    let input = solid_texture(gl, [200, 100, 0, 255]);
+    let output = RenderTarget::builder(SIZE, SIZE)
+        .color(ColorFormat::RGBA8)
+        .build(gl)
+        .unwrap();
+    let mut stack = PostProcessStack::new(gl, SIZE, SIZE, ColorFormat::RGBA8, None).unwrap();
+
+    // no effects copies the input
+    stack.apply(gl, input, Some(&output));
+    assert!(all_near(&read_output(gl), [200, 100, 0, 255]));
+
+    stack.effects = vec![Effect::Inversion];
+    stack.apply(gl, input, Some(&output));
+    assert!(all_near(&read_output(gl), [55, 155, 255, 255]));
+
+    // the second effect reads what the first one wrote
+    stack.effects = vec![Effect::Inversion, Effect::Inversion];
+    stack.apply(gl, input, Some(&output));
+    assert!(all_near(&read_output(gl), [200, 100, 0, 255]));
+}
+
+#[test]
+fn custom_effects_run_the_given_shader() {
+    let Some(context) = HeadlessContext::new(3, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let gl = &context.gl;
+    let input = solid_texture(gl, [200, 100, 0, 255]);
+    let output = RenderTarget::builder(SIZE, SIZE)
+        .color(ColorFormat::RGBA8)
+        .build(gl)
+        .unwrap();
+    let mut stack = PostProcessStack::new(gl, SIZE, SIZE, ColorFormat::RGBA8, None).unwrap();
+    let shader =
+        MyShader::new_from_source(gl, PostProcessStack::VERTEX_SHADER, SWIZZLE, None).unwrap();
+    let swizzle = stack.add_custom(gl, shader);
+    let custom = stack.custom_shader(swizzle);
+    custom.use_shader(gl);
+    custom.set_float(gl, "strength", 1.0);
+
+    stack.effects = vec![Effect::Custom(swizzle)];
+    stack.apply(gl, input, Some(&output));
+    assert!(all_near(&read_output(gl), [0, 100, 200, 255]));
+
+    // mixed with built-in effects, with its own uniform changed in between
+    let custom = stack.custom_shader(swizzle);
+    custom.use_shader(gl);
+    custom.set_float(gl, "strength", 0.5);
+    stack.effects = vec![Effect::Custom(swizzle), Effect::Inversion];
+    stack.apply(gl, input, Some(&output));
+    assert!(all_near(&read_output(gl), [255, 205, 155, 255]));
+}
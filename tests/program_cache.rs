@@ -0,0 +1,93 @@
+//! Builds programs on a headless context with `LEARN_OPENGL_PROGRAM_CACHE` pointing at a fresh
+//! directory and checks that building one again links the saved binary instead of the sources,
+//! and that a full cache drops the binaries used longest ago.
+//!
+//! Skipped without an EGL device or when the driver can't hand out program binaries.
+
+use lib_learn_opengl_rs::{HeadlessContext, MyShader};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// `MAX_ENTRIES` of the cache.
+const MAX_ENTRIES: usize = 256;
+
+const VERTEX: &str = r#"#version 330 core
+void main() {
+    gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+fn fragment(uniform: &str) -> String {
+    format!(
+        "#version 330 core\nout vec4 FragColor;\nuniform float {uniform};\n\
+         void main() {{\n    FragColor = vec4({uniform});\n}}\n"
+    )
+}
+
+fn binaries(dir: &Path) -> BTreeSet<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.map(|entry| entry.unwrap().path()).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn second_build_links_the_saved_binary() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("program_cache");
+    let _ = std::fs::remove_dir_all(&dir);
+    // only this test runs in this binary, nothing else reads the variable concurrently
+    std::env::set_var("LEARN_OPENGL_PROGRAM_CACHE", &dir);
+
+    let Some(context) = HeadlessContext::new(3, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let gl = &context.gl;
+
+    let first = MyShader::new_from_source(gl, VERTEX, &fragment("first"), None).unwrap();
+    let saved = binaries(&dir);
+    if saved.is_empty() {
+        println!("skipped: the driver has no program binaries");
+        return;
+    }
+    assert_eq!(saved.len(), 1);
+    let first_binary = saved.into_iter().next().unwrap();
+    first.use_shader(gl);
+    assert!(first.try_set_float(gl, "first", 1.0));
+
+    let second = MyShader::new_from_source(gl, VERTEX, &fragment("second"), None).unwrap();
+    let second_binary = binaries(&dir)
+        .into_iter()
+        .find(|path| *path != first_binary)
+        .expect("the second program was not saved");
+    second.use_shader(gl);
+    assert!(second.try_set_float(gl, "second", 1.0));
+
+    // the same sources again, but the saved binary is now the second program, so a program
+    // with the second program's uniform proves it came from the cache and not from compiling
+    std::fs::copy(&second_binary, &first_binary).unwrap();
+    let cached = MyShader::new_from_source(gl, VERTEX, &fragment("first"), None).unwrap();
+    cached.use_shader(gl);
+    assert!(cached.try_set_float(gl, "second", 1.0));
+    assert!(!cached.try_set_float(gl, "first", 1.0));
+    assert_eq!(binaries(&dir).len(), 2);
+
+    // fill the cache with binaries last used an hour ago and longer
+    let stale = |i: usize| dir.join(format!("stale{:04}.bin", i));
+    let an_hour_ago = SystemTime::now() - Duration::from_secs(3600);
+    for i in 0..MAX_ENTRIES {
+        std::fs::write(stale(i), [0u8; 8]).unwrap();
+        let file = File::options().write(true).open(stale(i)).unwrap();
+        file.set_modified(an_hour_ago - Duration::from_secs(i as u64))
+            .unwrap();
+    }
+    let _third = MyShader::new_from_source(gl, VERTEX, &fragment("third"), None).unwrap();
+    let left = binaries(&dir);
+    assert_eq!(left.len(), MAX_ENTRIES);
+    assert!(left.contains(&first_binary) && left.contains(&second_binary));
+    for i in MAX_ENTRIES - 3..MAX_ENTRIES {
+        assert!(!left.contains(&stale(i)), "{:?} was kept", stale(i));
+    }
+    assert!(left.contains(&stale(MAX_ENTRIES - 4)));
+}
@@ -0,0 +1,87 @@
+//! Builds and resizes `RenderTarget`s on a headless context, including a resize the driver refuses.
+//!
+//! Skipped without an EGL device.
+
+use glow::HasContext;
+use lib_learn_opengl_rs::{
+    ColorFormat, DepthAttachment, HeadlessContext, RenderTarget, RenderTargetError,
+};
+
+fn status(gl: &glow::Context, target: &RenderTarget) -> u32 {
+    unsafe {
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(target.framebuffer()));
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+        status
+    }
+}
+
+fn bound_framebuffer(gl: &glow::Context) -> i32 {
+    unsafe { gl.get_parameter_i32(glow::FRAMEBUFFER_BINDING) }
+}
+
+#[test]
+fn resize_recreates_the_attachments() {
+    let Some(context) = HeadlessContext::new(3, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let gl = &context.gl;
+    let mut target = RenderTarget::builder(32, 32)
+        .color(ColorFormat::RGBA8)
+        .color(ColorFormat::RGBA16F)
+        .depth(DepthAttachment::DepthStencil)
+        .build(gl)
+        .unwrap();
+    let texture = target.color_texture(1).unwrap();
+
+    target.resize(gl, 64, 48).unwrap();
+    assert_eq!((target.width(), target.height()), (64, 48));
+    assert_ne!(target.color_texture(1), Some(texture));
+    assert_eq!(status(gl, &target), glow::FRAMEBUFFER_COMPLETE);
+    assert_eq!(bound_framebuffer(gl), 0);
+
+    // a minimized window
+    target.resize(gl, 0, 0).unwrap();
+    assert_eq!((target.width(), target.height()), (64, 48));
+
+    let mut multisampled = RenderTarget::builder(32, 32)
+        .color(ColorFormat::RGBA8)
+        .depth(DepthAttachment::DepthStencil)
+        .samples(4)
+        .build(gl)
+        .unwrap();
+    assert_eq!(multisampled.color_texture(0), None);
+    multisampled.resize(gl, 16, 16).unwrap();
+    assert_eq!(status(gl, &multisampled), glow::FRAMEBUFFER_COMPLETE);
+}
+
+#[test]
+fn failed_resize_keeps_the_old_attachments() {
+    let Some(context) = HeadlessContext::new(3, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let gl = &context.gl;
+    let mut target = RenderTarget::builder(32, 32)
+        .color(ColorFormat::RGBA8)
+        .depth(DepthAttachment::Texture)
+        .build(gl)
+        .unwrap();
+    let (color, depth) = (target.color_texture(0), target.depth_texture());
+
+    // textures this wide get no storage, so the framebuffer would be incomplete
+    let too_wide = unsafe { gl.get_parameter_i32(glow::MAX_TEXTURE_SIZE) } as u32 * 2;
+    let error = target.resize(gl, too_wide, 32).unwrap_err();
+    assert!(
+        matches!(error, RenderTargetError::Incomplete(_)),
+        "{}",
+        error
+    );
+
+    assert_eq!((target.width(), target.height()), (32, 32));
+    assert_eq!(target.color_texture(0), color);
+    assert_eq!(target.depth_texture(), depth);
+    assert_eq!(status(gl, &target), glow::FRAMEBUFFER_COMPLETE);
+    assert_eq!(bound_framebuffer(gl), 0);
+}
@@ -0,0 +1,188 @@
+//! `#include` expansion of `MyShader::expand_includes` and the info log remapping of `PreprocessedSource`,
+//! no GL context needed.
+
+use lib_learn_opengl_rs::{MyShader, PreprocessedSource};
+use std::collections::HashMap;
+
+fn sources(files: &[(&str, &str)]) -> HashMap<String, String> {
+    files
+        .iter()
+        .map(|(file, source)| (file.to_string(), source.to_string()))
+        .collect()
+}
+
+/// Follows the `#line` directives like a GLSL compiler, returns the source string number and line
+/// each line of `source` is reported at, `None` for the directives themselves.
+fn reported_locations(source: &str) -> Vec<Option<(usize, u32)>> {
+    let (mut index, mut number) = (0, 1);
+    source
+        .lines()
+        .map(|line| {
+            if let Some(rest) = line.strip_prefix("#line ") {
+                let mut parts = rest.split_whitespace();
+                number = parts.next().unwrap().parse().unwrap();
+                index = parts.next().unwrap().parse().unwrap();
+                None
+            } else {
+                number += 1;
+                Some((index, number - 1))
+            }
+        })
+        .collect()
+}
+
+/// File and line the compiler reports for the expanded line containing `marker`.
+fn location_of<'a>(preprocessed: &'a PreprocessedSource, marker: &str) -> (&'a str, u32) {
+    let locations = reported_locations(&preprocessed.source);
+    let position = preprocessed
+        .source
+        .lines()
+        .position(|line| line.contains(marker))
+        .unwrap_or_else(|| panic!("{} is not in\n{}", marker, preprocessed.source));
+    let (index, line) = locations[position].unwrap();
+    (preprocessed.files[index].as_str(), line)
+}
+
+#[test]
+fn source_without_includes_is_unchanged() {
+    let source = "#version 330 core\nvoid main() {}\n";
+    let preprocessed =
+        MyShader::expand_includes("shaders/a.fs", &sources(&[("shaders/a.fs", source)])).unwrap();
+    assert_eq!(preprocessed.source, source);
+    assert_eq!(preprocessed.files, ["shaders/a.fs"]);
+}
+
+#[test]
+fn nested_includes_resolve_relative_to_the_including_file() {
+    let files = sources(&[
+        (
+            "shaders/main.fs",
+            "#version 330 core\n#include \"lib/lighting.glsl\"\nvoid main() { main_marker; }\n",
+        ),
+        (
+            "shaders/lib/lighting.glsl",
+            "// lighting\n#include \"../common/math.glsl\"\nfloat lighting_marker;\n",
+        ),
+        (
+            "shaders/common/math.glsl",
+            "#version 330 core\nfloat math_marker;\n",
+        ),
+    ]);
+    let preprocessed = MyShader::expand_includes("shaders/main.fs", &files).unwrap();
+
+    assert_eq!(
+        preprocessed.files,
+        [
+            "shaders/main.fs",
+            "shaders/lib/lighting.glsl",
+            "shaders/common/math.glsl"
+        ]
+    );
+    assert!(preprocessed.source.starts_with("#version 330 core\n"));
+    assert_eq!(preprocessed.source.matches("#version").count(), 2);
+    assert!(preprocessed.source.contains("// #version 330 core\n"));
+    assert!(!preprocessed.source.contains("#include"));
+    assert_eq!(
+        location_of(&preprocessed, "math_marker"),
+        ("shaders/common/math.glsl", 2)
+    );
+    assert_eq!(
+        location_of(&preprocessed, "lighting_marker"),
+        ("shaders/lib/lighting.glsl", 3)
+    );
+    assert_eq!(
+        location_of(&preprocessed, "main_marker"),
+        ("shaders/main.fs", 3)
+    );
+}
+
+#[test]
+fn each_file_is_included_once() {
+    let files = sources(&[
+        (
+            "shaders/main.fs",
+            "#version 330 core\n#include \"a.glsl\"\n#include \"b.glsl\"\n#include \"a.glsl\"\nfloat main_marker;\n",
+        ),
+        ("shaders/a.glsl", "#include \"common.glsl\"\nfloat a_marker;\n"),
+        ("shaders/b.glsl", "#include \"common.glsl\"\nfloat b_marker;\n"),
+        ("shaders/common.glsl", "float common_marker;\n"),
+    ]);
+    let preprocessed = MyShader::expand_includes("shaders/main.fs", &files).unwrap();
+
+    assert_eq!(
+        preprocessed.files,
+        [
+            "shaders/main.fs",
+            "shaders/a.glsl",
+            "shaders/common.glsl",
+            "shaders/b.glsl"
+        ]
+    );
+    assert_eq!(preprocessed.source.matches("common_marker").count(), 1);
+    assert_eq!(preprocessed.source.matches("a_marker").count(), 1);
+    assert!(preprocessed
+        .source
+        .contains("// shaders/common.glsl already included\n"));
+    assert!(preprocessed
+        .source
+        .contains("// shaders/a.glsl already included\n"));
+    assert_eq!(
+        location_of(&preprocessed, "b_marker"),
+        ("shaders/b.glsl", 2)
+    );
+    assert_eq!(
+        location_of(&preprocessed, "main_marker"),
+        ("shaders/main.fs", 5)
+    );
+}
+
+#[test]
+fn missing_and_malformed_includes_are_errors() {
+    let missing = sources(&[("shaders/main.fs", "#include \"missing.glsl\"\n")]);
+    let error = MyShader::expand_includes("shaders/main.fs", &missing).unwrap_err();
+    assert!(error.contains("shaders/missing.glsl"), "{}", error);
+
+    let malformed = sources(&[("shaders/main.fs", "#include <missing.glsl>\n")]);
+    let error = MyShader::expand_includes("shaders/main.fs", &malformed).unwrap_err();
+    assert!(error.contains("malformed include"), "{}", error);
+}
+
+#[test]
+fn info_log_source_numbers_become_file_names() {
+    let preprocessed = PreprocessedSource {
+        source: String::new(),
+        files: vec![
+            "shaders/main.fs".to_string(),
+            "shaders/lib/lighting.glsl".to_string(),
+        ],
+    };
+    // Mesa and ANGLE
+    assert_eq!(
+        preprocessed.remap_info_log("1:12(5): error: `foo' undeclared\n0:3(1): warning: unused"),
+        "shaders/lib/lighting.glsl:12(5): error: `foo' undeclared\nshaders/main.fs:3(1): warning: unused"
+    );
+    // NVIDIA
+    assert_eq!(
+        preprocessed.remap_info_log("1(12) : error C1008: undefined variable \"foo\""),
+        "shaders/lib/lighting.glsl(12) : error C1008: undefined variable \"foo\""
+    );
+    // unknown source numbers and numbers inside words stay as they are
+    assert_eq!(
+        preprocessed.remap_info_log("7:2(1): error: vec3:4 mismatch"),
+        "7:2(1): error: vec3:4 mismatch"
+    );
+    assert_eq!(
+        preprocessed.remap_info_log("ERROR: 0:4: 'x' : undeclared identifier"),
+        "ERROR: shaders/main.fs:4: 'x' : undeclared identifier"
+    );
+}
+
+#[test]
+fn info_log_of_a_single_file_is_unchanged() {
+    let preprocessed = PreprocessedSource {
+        source: String::new(),
+        files: vec!["shaders/main.fs".to_string()],
+    };
+    let info_log = "0:3(1): error: syntax error";
+    assert_eq!(preprocessed.remap_info_log(info_log), info_log);
+}
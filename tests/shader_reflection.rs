@@ -0,0 +1,79 @@
+//! Reflects a program with attributes, plain uniforms and a uniform block on a headless context.
+//!
+//! Skipped without an EGL device.
+
+use lib_learn_opengl_rs::{
+    ActiveBlock, ActiveUniform, HeadlessContext, MyShader, ShaderReflection,
+};
+
+const VERTEX: &str = r#"#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec2 aTexCoords;
+
+layout (std140) uniform Matrices {
+    mat4 projection;
+    mat4 view;
+};
+uniform mat4 model;
+
+out vec2 TexCoords;
+
+void main() {
+    TexCoords = aTexCoords;
+    gl_Position = projection * view * model * vec4(aPos, 1.0);
+}
+"#;
+
+const FRAGMENT: &str = r#"#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+uniform sampler2D diffuse;
+uniform float weights[3];
+
+void main() {
+    FragColor = texture(diffuse, TexCoords) * (weights[0] + weights[1] + weights[2]);
+}
+"#;
+
+fn uniform<'a>(uniforms: &'a [ActiveUniform], name: &str) -> &'a ActiveUniform {
+    uniforms
+        .iter()
+        .find(|uniform| uniform.name == name)
+        .unwrap_or_else(|| panic!("no uniform {} in {:?}", name, uniforms))
+}
+
+#[test]
+fn lists_attributes_uniforms_and_blocks() {
+    let Some(context) = HeadlessContext::new(3, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let gl = &context.gl;
+    let shader = MyShader::new_from_source(gl, VERTEX, FRAGMENT, None).unwrap();
+    let reflection: ShaderReflection = shader.reflect(gl);
+
+    let mut attributes = reflection
+        .attributes
+        .iter()
+        .map(|attribute| (attribute.name.as_str(), attribute.location))
+        .collect::<Vec<_>>();
+    attributes.sort();
+    assert_eq!(attributes, [("aPos", Some(0)), ("aTexCoords", Some(1))]);
+
+    // block members are only listed with their block
+    assert_eq!(reflection.uniforms.len(), 3, "{:?}", reflection.uniforms);
+    assert_eq!(uniform(&reflection.uniforms, "model").type_name(), "mat4");
+    assert_eq!(
+        uniform(&reflection.uniforms, "diffuse").type_name(),
+        "sampler2D"
+    );
+    assert_eq!(uniform(&reflection.uniforms, "weights[0]").size, 3);
+
+    let [block]: &[ActiveBlock; 1] = reflection.blocks.as_slice().try_into().unwrap();
+    assert_eq!(block.name, "Matrices");
+    assert_eq!(block.data_size, 128);
+    assert_eq!(block.members.len(), 2);
+    assert_eq!(uniform(&block.members, "projection").type_name(), "mat4");
+}
@@ -0,0 +1,121 @@
+//! Byte layout of `std140_bytes` for `uniform_block!` structs and plain values against the
+//! offsets the std140 rules give, no GL context needed.
+
+use lib_learn_opengl_rs::{std140_bytes, uniform_block};
+use nalgebra_glm as glm;
+
+uniform_block! {
+    struct Light {
+        position: glm::Vec3,
+        intensity: f32,
+        color: glm::Vec3,
+    }
+}
+
+uniform_block! {
+    /// `struct Block { float scale; Light lights[2]; mat3 normal; }`
+    struct Block {
+        scale: f32,
+        lights: [Light; 2],
+        normal: glm::Mat3,
+    }
+}
+
+uniform_block! {
+    struct Flags {
+        enabled: bool,
+        color: glm::Vec3,
+    }
+}
+
+fn floats(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+fn float_at(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn float_after_vec3_fills_its_padding() {
+    let light = Light {
+        position: glm::vec3(1.0, 2.0, 3.0),
+        intensity: 4.0,
+        color: glm::vec3(5.0, 6.0, 7.0),
+    };
+    let bytes = std140_bytes(&light);
+    // position at 0, intensity at 12, color at 16, struct size rounded up to 32
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(floats(&bytes), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 0.0]);
+}
+
+#[test]
+fn mat3_columns_are_padded_to_vec4() {
+    let matrix = glm::mat3(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0);
+    let bytes = std140_bytes(&matrix);
+    assert_eq!(bytes.len(), 48);
+    assert_eq!(
+        floats(&bytes),
+        [1.0, 4.0, 7.0, 0.0, 2.0, 5.0, 8.0, 0.0, 3.0, 6.0, 9.0, 0.0]
+    );
+}
+
+#[test]
+fn array_elements_have_a_vec4_stride() {
+    let bytes = std140_bytes(&[1.0f32, 2.0, 3.0]);
+    assert_eq!(bytes.len(), 48);
+    assert_eq!(
+        floats(&bytes),
+        [1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 3.0, 0.0, 0.0, 0.0]
+    );
+
+    let bytes = std140_bytes(&[glm::vec2(1.0, 2.0), glm::vec2(3.0, 4.0)]);
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(float_at(&bytes, 16), 3.0);
+
+    // arrays of vec4 need no padding
+    let bytes = std140_bytes(&[glm::vec4(1.0, 2.0, 3.0, 4.0); 2]);
+    assert_eq!(bytes.len(), 32);
+}
+
+#[test]
+fn nested_structs_and_arrays_in_a_block() {
+    let light = |base: f32| Light {
+        position: glm::vec3(base, base, base),
+        intensity: base + 1.0,
+        color: glm::vec3(base + 2.0, base + 2.0, base + 2.0),
+    };
+    let block = Block {
+        scale: 0.5,
+        lights: [light(10.0), light(20.0)],
+        normal: glm::Mat3::identity(),
+    };
+    let bytes = std140_bytes(&block);
+    // scale at 0, lights[0] at 16, lights[1] at 48, normal at 80, 80 + 48 = 128
+    assert_eq!(bytes.len(), 128);
+    assert_eq!(float_at(&bytes, 0), 0.5);
+    assert_eq!(float_at(&bytes, 16), 10.0);
+    assert_eq!(float_at(&bytes, 28), 11.0);
+    assert_eq!(float_at(&bytes, 32), 12.0);
+    assert_eq!(float_at(&bytes, 48), 20.0);
+    assert_eq!(float_at(&bytes, 60), 21.0);
+    assert_eq!(float_at(&bytes, 80), 1.0);
+    assert_eq!(float_at(&bytes, 96 + 4), 1.0);
+    assert_eq!(float_at(&bytes, 112 + 8), 1.0);
+}
+
+#[test]
+fn bools_take_four_bytes() {
+    let flags = Flags {
+        enabled: true,
+        color: glm::vec3(1.0, 2.0, 3.0),
+    };
+    let bytes = std140_bytes(&flags);
+    // enabled at 0, color at 16, 28 rounded up to 32
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(u32::from_ne_bytes(bytes[0..4].try_into().unwrap()), 1);
+    assert_eq!(float_at(&bytes, 16), 1.0);
+}
@@ -0,0 +1,170 @@
+//! Tessellates a quad patch on a headless OpenGL 4.0 context, counting the triangles it becomes
+//! and reading the covered pixels back.
+//!
+//! Skipped without an EGL device or when the driver has no tessellation shaders, Mesa llvmpipe has both.
+
+use glow::HasContext;
+use lib_learn_opengl_rs::{HeadlessContext, MyShader};
+
+const VERTEX: &str = r#"#version 400 core
+const vec2 corners[4] = vec2[](vec2(-1.0, -1.0), vec2(1.0, -1.0), vec2(1.0, 1.0), vec2(-1.0, 1.0));
+
+void main() {
+    gl_Position = vec4(corners[gl_VertexID], 0.0, 1.0);
+}
+"#;
+
+const TESS_CONTROL: &str = r#"#version 400 core
+layout(vertices = 4) out;
+
+uniform float level;
+
+void main() {
+    gl_out[gl_InvocationID].gl_Position = gl_in[gl_InvocationID].gl_Position;
+    if (gl_InvocationID == 0) {
+        gl_TessLevelOuter[0] = level;
+        gl_TessLevelOuter[1] = level;
+        gl_TessLevelOuter[2] = level;
+        gl_TessLevelOuter[3] = level;
+        gl_TessLevelInner[0] = level;
+        gl_TessLevelInner[1] = level;
+    }
+}
+"#;
+
+const TESS_EVALUATION: &str = r#"#version 400 core
+layout(quads, equal_spacing, ccw) in;
+
+void main() {
+    vec4 bottom = mix(gl_in[0].gl_Position, gl_in[1].gl_Position, gl_TessCoord.x);
+    vec4 top = mix(gl_in[3].gl_Position, gl_in[2].gl_Position, gl_TessCoord.x);
+    gl_Position = mix(bottom, top, gl_TessCoord.y);
+}
+"#;
+
+const FRAGMENT: &str = r#"#version 400 core
+out vec4 FragColor;
+
+void main() {
+    FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+fn tessellation_context() -> Option<HeadlessContext> {
+    let Some(context) = HeadlessContext::new(4, 0) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return None;
+    };
+    if !MyShader::supports_tessellation(&context.gl) {
+        println!("skipped: the driver has no tessellation shaders");
+        return None;
+    }
+    Some(context)
+}
+
+/// Draws one patch of the 4 corners, returns the number of triangles it was tessellated into
+/// and the pixels of the 16x16 pbuffer.
+fn draw_patch(context: &HeadlessContext, shader: &MyShader) -> (u32, Vec<u8>) {
+    let gl = &context.gl;
+    let mut pixels = vec![0u8; 16 * 16 * 4];
+    unsafe {
+        // the corners come from gl_VertexID, but core profiles still need a vertex array bound
+        let vao = gl.create_vertex_array().unwrap();
+        gl.bind_vertex_array(Some(vao));
+        gl.viewport(0, 0, 16, 16);
+        gl.clear_color(0.0, 0.0, 0.0, 1.0);
+        gl.clear(glow::COLOR_BUFFER_BIT);
+
+        shader.use_shader(gl);
+        MyShader::set_patch_vertices(gl, 4);
+        let query = gl.create_query().unwrap();
+        gl.begin_query(glow::PRIMITIVES_GENERATED, query);
+        gl.draw_arrays(glow::PATCHES, 0, 4);
+        gl.end_query(glow::PRIMITIVES_GENERATED);
+        let triangles = gl.get_query_parameter_u32(query, glow::QUERY_RESULT);
+
+        gl.read_pixels(
+            0,
+            0,
+            16,
+            16,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelPackData::Slice(&mut pixels),
+        );
+        gl.delete_query(query);
+        gl.delete_vertex_array(vao);
+        (triangles, pixels)
+    }
+}
+
+fn all_red(pixels: &[u8]) -> bool {
+    pixels
+        .chunks_exact(4)
+        .all(|pixel| pixel == [255, 0, 0, 255])
+}
+
+#[test]
+fn control_stage_sets_the_levels() {
+    let Some(context) = tessellation_context() else {
+        return;
+    };
+    let gl = &context.gl;
+    let shader = MyShader::new_with_tessellation_from_source(
+        gl,
+        VERTEX,
+        Some(TESS_CONTROL),
+        TESS_EVALUATION,
+        None,
+        FRAGMENT,
+    )
+    .unwrap();
+    shader.use_shader(gl);
+    shader.set_float(gl, "level", 4.0);
+
+    let (triangles, pixels) = draw_patch(&context, &shader);
+    // a 4x4 grid of quads, two triangles each
+    assert_eq!(triangles, 32);
+    assert!(all_red(&pixels));
+}
+
+#[test]
+fn levels_default_to_one_without_a_control_stage() {
+    let Some(context) = tessellation_context() else {
+        return;
+    };
+    let gl = &context.gl;
+    let shader = MyShader::new_with_tessellation_from_source(
+        gl,
+        VERTEX,
+        None,
+        TESS_EVALUATION,
+        None,
+        FRAGMENT,
+    )
+    .unwrap();
+
+    let (triangles, pixels) = draw_patch(&context, &shader);
+    assert_eq!(triangles, 2);
+    assert!(all_red(&pixels));
+}
+
+#[test]
+fn errors_name_the_tessellation_stage() {
+    let Some(context) = tessellation_context() else {
+        return;
+    };
+    let broken = TESS_EVALUATION.replace("gl_TessCoord.y);", "gl_TessCoord.y)");
+    let Err(error) = MyShader::new_with_tessellation_from_source(
+        &context.gl,
+        VERTEX,
+        Some(TESS_CONTROL),
+        &broken,
+        None,
+        FRAGMENT,
+    ) else {
+        panic!("a missing semicolon compiled");
+    };
+    let message = error.to_string();
+    assert!(message.contains("tessellation evaluation"), "{}", message);
+}
@@ -0,0 +1,107 @@
+//! Formats and filters `TextureFormat::of` picks for 8 bit and float images, no GL context needed.
+
+use glow::{
+    FLOAT, LINEAR, LINEAR_MIPMAP_LINEAR, NEAREST, R8, RED, RGB, RGB16F, RGB8, RGBA, RGBA16F,
+    RGBA32F, RGBA8, SRGB8, SRGB8_ALPHA8, UNSIGNED_BYTE,
+};
+use image::{DynamicImage, Rgb32FImage, RgbImage, Rgba32FImage};
+use lib_learn_opengl_rs::{TextureChannels, TextureFormat, TextureOptions};
+
+/// The largest finite half float.
+const HALF_MAX: f32 = 65504.0;
+
+fn rgb_float(value: f32) -> DynamicImage {
+    DynamicImage::ImageRgb32F(Rgb32FImage::from_fn(2, 2, |x, y| {
+        // one texel has `value`, the rest fit any format
+        if (x, y) == (1, 1) {
+            image::Rgb([0.5, value, 0.5])
+        } else {
+            image::Rgb([0.5, 0.5, 0.5])
+        }
+    }))
+}
+
+#[test]
+fn eight_bit_images_follow_the_options() {
+    let img = DynamicImage::ImageRgb8(RgbImage::new(2, 2));
+    let format = |options: TextureOptions| {
+        let format = TextureFormat::of(&img, &options);
+        (format.internal_format, format.format, format.data_type)
+    };
+
+    assert_eq!(
+        format(TextureOptions::default()),
+        (RGBA8, RGBA, UNSIGNED_BYTE)
+    );
+    assert_eq!(
+        format(TextureOptions::default().srgb(true)),
+        (SRGB8_ALPHA8, RGBA, UNSIGNED_BYTE)
+    );
+    let rgb = TextureOptions {
+        channels: TextureChannels::Rgb,
+        ..TextureOptions::default()
+    };
+    assert_eq!(format(rgb), (RGB8, RGB, UNSIGNED_BYTE));
+    assert_eq!(format(rgb.srgb(true)), (SRGB8, RGB, UNSIGNED_BYTE));
+    // no sRGB format with one channel
+    let red = TextureOptions {
+        channels: TextureChannels::R,
+        ..TextureOptions::default()
+    };
+    assert_eq!(format(red.srgb(true)), (R8, RED, UNSIGNED_BYTE));
+
+    let format = TextureFormat::of(&img, &TextureOptions::default());
+    assert!(format.generate_mipmaps);
+    assert_eq!(
+        (format.min_filter, format.mag_filter),
+        (LINEAR_MIPMAP_LINEAR, LINEAR)
+    );
+}
+
+#[test]
+fn float_images_use_half_floats_while_the_values_fit() {
+    let options = TextureOptions::default();
+    let format = TextureFormat::of(&rgb_float(HALF_MAX), &options);
+    assert_eq!(
+        (format.internal_format, format.format, format.data_type),
+        (RGB16F, RGB, FLOAT)
+    );
+    let format = TextureFormat::of(&rgb_float(-HALF_MAX), &options);
+    assert_eq!(format.internal_format, RGB16F);
+
+    let rgba = DynamicImage::ImageRgba32F(Rgba32FImage::from_pixel(2, 2, image::Rgba([1.0; 4])));
+    let format = TextureFormat::of(&rgba, &options);
+    assert_eq!(
+        (format.internal_format, format.format, format.data_type),
+        (RGBA16F, RGBA, FLOAT)
+    );
+    // float textures never get mipmaps, the filter drops its mipmap part
+    assert!(!format.generate_mipmaps);
+    assert_eq!((format.min_filter, format.mag_filter), (LINEAR, LINEAR));
+}
+
+#[test]
+fn float_images_beyond_the_half_range_use_full_floats() {
+    let options = TextureOptions::default();
+    for value in [HALF_MAX * 2.0, -HALF_MAX * 2.0, f32::INFINITY, f32::NAN] {
+        let format = TextureFormat::of(&rgb_float(value), &options);
+        // RGB images get an alpha channel, RGB32F isn't color renderable
+        assert_eq!(
+            (format.internal_format, format.format, format.data_type),
+            (RGBA32F, RGBA, FLOAT),
+            "{}",
+            value
+        );
+    }
+}
+
+#[test]
+fn full_float_filters_follow_the_options_on_desktop() {
+    let img = rgb_float(HALF_MAX * 2.0);
+    let format = TextureFormat::of(&img, &TextureOptions::default());
+    assert_eq!((format.min_filter, format.mag_filter), (LINEAR, LINEAR));
+
+    let nearest = TextureOptions::default().filter(NEAREST, NEAREST);
+    let format = TextureFormat::of(&img, &nearest);
+    assert_eq!((format.min_filter, format.mag_filter), (NEAREST, NEAREST));
+}
@@ -0,0 +1,71 @@
+//! Setting uniforms of the wrong type or array size on a headless context: `try_set_*` returns
+//! `false` where `set_*` panics.
+//!
+//! Skipped without an EGL device.
+
+use lib_learn_opengl_rs::{HeadlessContext, MyShader};
+use nalgebra_glm as glm;
+use std::panic::AssertUnwindSafe;
+
+const VERTEX: &str = r#"#version 330 core
+void main() {
+    gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT: &str = r#"#version 330 core
+out vec4 FragColor;
+
+uniform float strength;
+uniform vec3 colors[2];
+
+void main() {
+    FragColor = vec4((colors[0] + colors[1]) * strength, 1.0);
+}
+"#;
+
+#[test]
+fn try_set_returns_false_on_a_mismatch() {
+    let Some(context) = HeadlessContext::new(3, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let gl = &context.gl;
+    let shader = MyShader::new_from_source(gl, VERTEX, FRAGMENT, None).unwrap();
+    shader.use_shader(gl);
+    let colors = [glm::vec3(1.0, 0.0, 0.0); 3];
+
+    assert!(shader.try_set_float(gl, "strength", 1.0));
+    assert!(shader.try_set_vec3_array(gl, "colors", &colors[..2]));
+    assert!(shader.try_set_vec3_array(gl, "colors[1]", &colors[..1]));
+    // the wrong type
+    assert!(!shader.try_set_int(gl, "strength", 1));
+    // more elements than are left
+    assert!(!shader.try_set_vec3_array(gl, "colors", &colors));
+    assert!(!shader.try_set_vec3_array(gl, "colors[1]", &colors[..2]));
+    // not a uniform at all
+    assert!(!shader.try_set_float(gl, "missing", 1.0));
+}
+
+#[test]
+fn set_panics_on_a_mismatch() {
+    let Some(context) = HeadlessContext::new(3, 3) else {
+        println!("skipped: no EGL device with pbuffer support to render on");
+        return;
+    };
+    let gl = &context.gl;
+    let shader = MyShader::new_from_source(gl, VERTEX, FRAGMENT, None).unwrap();
+    shader.use_shader(gl);
+
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        shader.set_int(gl, "strength", 1);
+    }));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("strength"), "{}", message);
+
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        shader.set_vec3_array(gl, "colors", &[glm::vec3(1.0, 0.0, 0.0); 3]);
+    }));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("elements left"), "{}", message);
+}